@@ -0,0 +1,126 @@
+// The codebase deliberately uses explicit returns
+#![allow(clippy::needless_return)]
+
+use advent_of_code::error::Result;
+use advent_of_code::input::{input_path, Input, Source};
+use advent_of_code::params::{DayParams, Params, PARAMS_FILE};
+use advent_of_code::runner::DAYS;
+use advent_of_code::solution::{Answer, Parsed, Solution};
+use advent_of_code::{budget, day11, day17, day18, day19, day6};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Duration;
+
+const DATA_DIR: &str = "data";
+
+// Parts that take longer than this on their first run are not benchmarked
+const TIME_LIMIT: Duration = Duration::from_secs(60);
+
+// Solves one part of a parsed input
+type Solve = fn(&dyn Parsed) -> Result<Answer>;
+
+// Real input of a day, or `None` if it is missing or empty, in which case the day is skipped
+fn read_input(day: usize) -> Option<String> {
+    let input = Input { source: Source::File(input_path(Path::new(DATA_DIR), day)), label: None };
+    return match input.read() {
+        Ok(contents) if !contents.is_empty() => Some(contents),
+        _ => {
+            eprintln!("Skipping day {}: no input in {}/day{}.txt", day, DATA_DIR, day);
+            None
+        }
+    };
+}
+
+fn day_params(day: usize) -> DayParams {
+    return Params::load(Path::new(PARAMS_FILE), false).unwrap().day(day);
+}
+
+// Whether a part is worth benchmarking, i.e. it is solved and finishes within the time limit without panicking
+fn solves(day: usize, part: u8, solve: &dyn Fn() -> Result<Answer>) -> bool {
+    return match panic::catch_unwind(AssertUnwindSafe(|| budget::with_limit(Some(TIME_LIMIT), solve))) {
+        Ok(Ok(Answer::Unsolved)) => false,
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            eprintln!("Skipping day {} part {}: {}", day, part, e);
+            false
+        }
+        Err(_) => {
+            eprintln!("Skipping day {} part {}: it panicked", day, part);
+            false
+        }
+    };
+}
+
+/// Parsing and both parts of every day that has an input
+fn days(c: &mut Criterion) {
+    for (idx, runner) in DAYS.iter().enumerate() {
+        let day = idx + 1;
+        let Some(contents) = read_input(day) else {
+            continue;
+        };
+        let params = day_params(day);
+        let parsed = match runner.parse_input(&contents, &params) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Skipping day {}: {}", day, e);
+                continue;
+            }
+        };
+        let mut group = c.benchmark_group(format!("day{}", day));
+        group.sample_size(10);
+        group.bench_function("parse", |b| b.iter(|| runner.parse_input(black_box(&contents), &params).is_ok()));
+        let parts: [(u8, Solve); 2] = [(1, |p| p.part1()), (2, |p| p.part2())];
+        for (part, solve) in parts {
+            if solves(day, part, &|| solve(parsed.as_ref())) {
+                group.bench_function(format!("part{}", part), |b| b.iter(|| solve(parsed.as_ref())));
+            }
+        }
+        group.finish();
+    }
+}
+
+/// The functions most of the time goes into for some of the days
+fn kernels(c: &mut Criterion) {
+    if let Some(contents) = read_input(6) {
+        let (grid, carot) = day6::Day6.parse(&contents).unwrap();
+        c.bench_function("day6::is_loop", |b| b.iter(|| day6::is_loop(black_box(&grid), carot)));
+    }
+    if let Some(contents) = read_input(11) {
+        let (params, stones) = day11::Day11.parse_with(&contents, &day_params(11)).unwrap();
+        c.bench_function("day11::recursive_soln", |b| {
+            b.iter(|| {
+                let mut cache = HashMap::new();
+                return stones.iter().map(|&stone| day11::recursive_soln(stone, params.part2_blinks, &mut cache)).sum::<usize>();
+            })
+        });
+    }
+    if let Some(contents) = read_input(17) {
+        let (state, _) = day17::read_contents(&contents).unwrap();
+        c.bench_function("day17::execute", |b| {
+            b.iter_batched(|| state.clone(), |mut state| day17::execute(&mut state), BatchSize::SmallInput)
+        });
+    }
+    if let Some(contents) = read_input(18) {
+        let (params, obstacles) = day18::Day18.parse_with(&contents, &day_params(18)).unwrap();
+        let graph = day18::create_graph(&obstacles[..params.part1_bytes.min(obstacles.len())], params.size);
+        let start = day18::linear_index((0, 0), params.size.1);
+        let end = day18::linear_index((params.size.0 - 1, params.size.1 - 1), params.size.1);
+        c.bench_function("day18::shortest_path", |b| {
+            b.iter_batched(|| graph.clone(), |graph| day18::shortest_path(graph, start, end), BatchSize::SmallInput)
+        });
+    }
+    if let Some(contents) = read_input(19) {
+        let (patterns, towels) = day19::Day19.parse(&contents).unwrap();
+        c.bench_function("day19::number_patterns", |b| {
+            b.iter(|| {
+                return patterns.iter().map(|pattern| day19::number_patterns(pattern, &towels, &mut HashMap::new())).sum::<usize>();
+            })
+        });
+    }
+}
+
+criterion_group!(benches, days, kernels);
+criterion_main!(benches);
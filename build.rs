@@ -0,0 +1,60 @@
+// Hashes the library's sources, so that the answer cache can tell when the implementation of a day changed.
+// Writes `sources.rs` to `OUT_DIR`, which `cache` includes.
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+include!("src/fnv.rs");
+
+// Modules that only the binary uses, and that cannot change any answers
+const BINARY_ONLY: [&str; 2] = ["main.rs", "cli.rs"];
+
+// Name of the day module a top level source file holds, e.g. `day21_2` for `src/day21_2.rs`
+fn day_module(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let number = stem.strip_prefix("day")?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || c == '_') {
+        return None;
+    }
+    return Some(stem.to_string());
+}
+
+// Every Rust source file under `dir`, sorted so that the hashes do not depend on the order of the directory
+fn sources(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(sources(&path));
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    return files;
+}
+
+fn main() {
+    let src = Path::new("src");
+    println!("cargo:rerun-if-changed=src");
+    let mut days = Vec::new();
+    let mut shared = FNV_OFFSET;
+    for path in sources(src) {
+        let contents = fs::read(&path).unwrap();
+        let top_level = path.parent() == Some(src);
+        match day_module(&path).filter(|_| top_level) {
+            Some(module) => days.push((module, fnv1a(FNV_OFFSET, &contents))),
+            None if top_level && BINARY_ONLY.iter().any(|name| path.ends_with(name)) => {}
+            None => shared = fnv1a(fnv1a(shared, path.to_string_lossy().as_bytes()), &contents),
+        }
+    }
+
+    let mut out = String::from("/// Hash of the source of each day module\npub const DAY_SOURCES: &[(&str, u64)] = &[\n");
+    for (module, hash) in days {
+        out += &format!("    (\"{}\", {:#018x}),\n", module, hash);
+    }
+    out += &format!("];\n\n/// Hash of the rest of the library, which every day relies on\npub const SHARED_SOURCE: u64 = {:#018x};\n", shared);
+    fs::write(PathBuf::from(env::var("OUT_DIR").unwrap()).join("sources.rs"), out).unwrap();
+}
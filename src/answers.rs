@@ -0,0 +1,142 @@
+use crate::error::{Error, Result};
+use crate::solution::Answer;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Expected answers are stored next to the input, e.g. `data/day6.txt` -> `data/day6.answers`,
+// with one `part: answer` line per part, e.g.
+//   1: 41
+//   2: 6
+pub fn answers_path(input_path: &Path) -> PathBuf {
+    return input_path.with_extension("answers");
+}
+
+/// Known correct answers for the two parts of a day
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl ExpectedAnswers {
+    /// Read the answers stored for an input, which are empty if nothing was recorded yet
+    pub fn load(input_path: &Path) -> Result<ExpectedAnswers> {
+        let path = answers_path(input_path);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ExpectedAnswers::default()),
+            Err(e) => return Err(Error::io(path, e)),
+        };
+        let mut expected = ExpectedAnswers::default();
+        for line in contents.lines() {
+            match line.split_once(':') {
+                Some(("1", answer)) => expected.part1 = Some(answer.trim().to_string()),
+                Some(("2", answer)) => expected.part2 = Some(answer.trim().to_string()),
+                _ => {}
+            }
+        }
+        return Ok(expected);
+    }
+
+    pub fn save(&self, input_path: &Path) -> Result<()> {
+        let mut contents = String::new();
+        if let Some(answer) = &self.part1 {
+            contents += &format!("1: {}\n", answer);
+        }
+        if let Some(answer) = &self.part2 {
+            contents += &format!("2: {}\n", answer);
+        }
+        let path = answers_path(input_path);
+        return fs::write(&path, contents).map_err(|e| Error::io(path, e));
+    }
+
+    pub fn part(&self, part: u8) -> &Option<String> {
+        return if part == 1 { &self.part1 } else { &self.part2 };
+    }
+
+    /// Record an answer for a part that has no stored answer yet, returning whether it was added
+    pub fn record(&mut self, part: u8, answer: &Answer) -> bool {
+        let slot = if part == 1 { &mut self.part1 } else { &mut self.part2 };
+        if slot.is_some() || *answer == Answer::Unsolved {
+            return false;
+        }
+        *slot = Some(answer.to_string());
+        return true;
+    }
+}
+
+/// Result of checking an answer against the stored one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail(String),
+    New,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Status::New => write!(f, "NEW"),
+        };
+    }
+}
+
+pub fn check(expected: &Option<String>, answer: &Answer) -> Status {
+    return match expected {
+        None => Status::New,
+        Some(expected) if *expected == answer.to_string() => Status::Pass,
+        Some(expected) => Status::Fail(expected.clone()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_and_save() {
+        let dir = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("day6.txt");
+        assert_eq!(answers_path(&input), dir.join("day6.answers"));
+        // Nothing recorded yet
+        assert_eq!(ExpectedAnswers::load(&input).unwrap(), ExpectedAnswers::default());
+
+        // Other lines are ignored and answers are trimmed
+        fs::write(answers_path(&input), "# day 6\n1:   41 \n3: 7\n\n2: a,b\n").unwrap();
+        let expected = ExpectedAnswers::load(&input).unwrap();
+        assert_eq!(expected, ExpectedAnswers { part1: Some("41".to_string()), part2: Some("a,b".to_string()) });
+
+        let only_part2 = ExpectedAnswers { part1: None, part2: Some("6".to_string()) };
+        only_part2.save(&input).unwrap();
+        assert_eq!(fs::read_to_string(answers_path(&input)).unwrap(), "2: 6\n");
+        assert_eq!(ExpectedAnswers::load(&input).unwrap(), only_part2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn record_new_answers_only() {
+        let mut expected = ExpectedAnswers::default();
+        assert!(!expected.record(1, &Answer::Unsolved));
+        assert_eq!(expected.part(1), &None);
+        assert!(expected.record(1, &Answer::UInt(41)));
+        // A stored answer is never overwritten
+        assert!(!expected.record(1, &Answer::UInt(42)));
+        assert!(expected.record(2, &Answer::Text("6,1".to_string())));
+        assert_eq!(expected.part(1), &Some("41".to_string()));
+        assert_eq!(expected.part(2), &Some("6,1".to_string()));
+    }
+
+    #[test]
+    fn check_answers() {
+        let expected = Some("41".to_string());
+        assert_eq!(check(&expected, &Answer::Int(41)), Status::Pass);
+        assert_eq!(check(&expected, &Answer::UInt(40)), Status::Fail("41".to_string()));
+        assert_eq!(check(&None, &Answer::UInt(40)), Status::New);
+        assert_eq!(Status::Fail("41".to_string()).to_string(), "FAIL, expected 41");
+    }
+}
@@ -0,0 +1,60 @@
+use crate::error::{Error, Result};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    // When the part running on this thread has to stop, and the limit it was given
+    static DEADLINE: Cell<Option<(Instant, Duration)>> = const { Cell::new(None) };
+    static CHECKS: Cell<u32> = const { Cell::new(0) };
+}
+
+// Only look at the clock every this many checks, so that checking in tight loops stays cheap
+const CHECKS_PER_CLOCK: u32 = 256;
+
+/// Run `f` with at most `limit` to spend, which the open-ended loops inside it enforce by calling `check`
+pub fn with_limit<T>(limit: Option<Duration>, f: impl FnOnce() -> T) -> T {
+    let previous = DEADLINE.replace(limit.map(|limit| (Instant::now() + limit, limit)));
+    CHECKS.set(0);
+    let ret = f();
+    DEADLINE.set(previous);
+    return ret;
+}
+
+/// Fail with `Error::Timeout` once the part being run has used up its time limit.
+/// Searches that have no bound, e.g. counting up until a value works, should call this on every iteration.
+pub fn check() -> Result<()> {
+    let Some((deadline, limit)) = DEADLINE.get() else {
+        return Ok(());
+    };
+    let checks = CHECKS.get().wrapping_add(1);
+    CHECKS.set(checks);
+    if checks.is_multiple_of(CHECKS_PER_CLOCK) && Instant::now() >= deadline {
+        return Err(Error::Timeout(limit));
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_forever() -> Result<()> {
+        loop {
+            check()?;
+        }
+    }
+
+    #[test]
+    fn loops_stop_at_the_limit() {
+        let limit = Duration::from_millis(20);
+        let err = with_limit(Some(limit), search_forever).unwrap_err();
+        assert!(matches!(err, Error::Timeout(l) if l == limit));
+        // The limit only applies inside `with_limit`
+        assert!(check().is_ok());
+    }
+
+    #[test]
+    fn no_limit() {
+        assert_eq!(with_limit(None, || (0..10_000).map(|_| check()).collect::<Result<Vec<_>>>().unwrap().len()), 10_000);
+    }
+}
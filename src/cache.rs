@@ -0,0 +1,177 @@
+use crate::error::{Error, Result};
+use crate::export;
+use crate::fnv::{fnv1a, FNV_OFFSET};
+use crate::params::DayParams;
+use crate::solution::{Answer, DayResult, PartResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Hashes of the sources, written by `build.rs`
+include!(concat!(env!("OUT_DIR"), "/sources.rs"));
+
+/// File the answers of earlier runs are cached in, which `cargo clean` clears
+pub const CACHE_FILE: &str = "target/answer-cache.json";
+
+/// Version of a day's implementation, which changes whenever the source of its module or of the shared
+/// library code changes. `type_name` is the full name of the solution type, e.g. `advent_of_code::day12::Day12Old`.
+pub fn version(type_name: &str) -> u64 {
+    let module = type_name.rsplit("::").nth(1).unwrap_or_default();
+    let day = DAY_SOURCES.iter().find(|(m, _)| *m == module).map_or(0, |(_, hash)| *hash);
+    return fnv1a(fnv1a(FNV_OFFSET, &day.to_le_bytes()), &SHARED_SOURCE.to_le_bytes());
+}
+
+/// Key of the cached answers of an implementation of a day, for an input and puzzle parameters
+pub fn key(day: usize, implementation: &str, contents: &str, params: &DayParams) -> String {
+    // The separator keeps the input and parameters from running into each other
+    let hash = fnv1a(fnv1a(fnv1a(FNV_OFFSET, contents.as_bytes()), &[0xff]), params.to_toml().as_bytes());
+    return format!("day{}/{}/{:016x}", day, implementation, hash);
+}
+
+/// Answer and time of a part from an earlier run, where an answer of `None` means the part is unsolved
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedPart {
+    pub answer: Option<String>,
+    pub time_ns: u64,
+}
+
+impl CachedPart {
+    /// Cache a part unless it failed, as errors and timeouts should be retried
+    pub fn new(result: &PartResult) -> Option<CachedPart> {
+        let answer = match result.answer.as_ref().ok()? {
+            Answer::Unsolved => None,
+            answer => Some(answer.to_string()),
+        };
+        return Some(CachedPart { answer, time_ns: export::nanos(result.time) });
+    }
+
+    pub fn result(&self) -> PartResult {
+        return PartResult {
+            answer: Ok(self.answer.clone().map_or(Answer::Unsolved, Answer::Text)),
+            time: Duration::from_nanos(self.time_ns),
+            memory: Default::default(),
+        };
+    }
+}
+
+/// Cached answers of a day on one input, which only count for the implementation they were solved with
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedDay {
+    pub version: u64,
+    pub parse_ns: u64,
+    pub part1: Option<CachedPart>,
+    pub part2: Option<CachedPart>,
+}
+
+impl CachedDay {
+    pub fn part(&self, part: u8) -> &Option<CachedPart> {
+        return if part == 1 { &self.part1 } else { &self.part2 };
+    }
+
+    /// These answers along with the ones from a new run, which replace the old ones for the parts it ran
+    pub fn updated(&self, result: &DayResult) -> CachedDay {
+        let new = |result: &Option<PartResult>| result.as_ref().and_then(CachedPart::new);
+        return CachedDay {
+            version: self.version,
+            parse_ns: export::nanos(result.parse_time),
+            part1: new(&result.part1).or(self.part1.clone()),
+            part2: new(&result.part2).or(self.part2.clone()),
+        };
+    }
+}
+
+/// Answers of earlier runs, which can be shared between days running in parallel
+#[derive(Debug, Default)]
+pub struct AnswerCache {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, CachedDay>>,
+}
+
+impl AnswerCache {
+    /// Read the cache from `path`. A missing or unreadable cache is empty, as it can always be filled again.
+    pub fn load(path: &Path) -> AnswerCache {
+        let entries = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::warn!("Ignoring the answer cache in {}: {}", path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        return AnswerCache { path: path.to_path_buf(), entries: Mutex::new(entries) };
+    }
+
+    /// Cached answers for `key`, which are empty if they were solved by another version of the implementation
+    pub fn get(&self, key: &str, version: u64) -> CachedDay {
+        return match self.entries.lock().unwrap().get(key) {
+            Some(day) if day.version == version => day.clone(),
+            _ => CachedDay { version, ..Default::default() },
+        };
+    }
+
+    pub fn insert(&self, key: String, day: CachedDay) {
+        self.entries.lock().unwrap().insert(key, day);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(&*self.entries.lock().unwrap()).map_err(io::Error::from);
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        }
+        return contents.and_then(|contents| fs::write(&self.path, contents)).map_err(|e| Error::io(&self.path, e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_follow_the_module() {
+        assert_eq!(version("advent_of_code::day12::Day12"), version("advent_of_code::day12::Day12Old"));
+        assert_ne!(version("advent_of_code::day21::Day21"), version("advent_of_code::day21_2::Day21"));
+        assert!(DAY_SOURCES.iter().any(|(module, _)| *module == "day21_2"));
+    }
+
+    #[test]
+    fn keys_depend_on_input_and_params() {
+        let params = DayParams::from_toml("size = [7, 7]").unwrap();
+        let k = key(18, "main", "5,4\n4,2", &params);
+        assert!(k.starts_with("day18/main/"));
+        assert_ne!(k, key(18, "main", "5,4\n4,3", &params));
+        assert_ne!(k, key(18, "main", "5,4\n4,2", &DayParams::default()));
+        assert_ne!(k, key(18, "other", "5,4\n4,2", &params));
+    }
+
+    #[test]
+    fn other_versions_miss() {
+        let cache = AnswerCache::default();
+        let part = CachedPart { answer: Some("42".to_string()), time_ns: 10 };
+        cache.insert("day1/main/0".to_string(), CachedDay { version: 1, parse_ns: 5, part1: Some(part.clone()), part2: None });
+        assert_eq!(cache.get("day1/main/0", 1).part1, Some(part));
+        assert_eq!(cache.get("day1/main/0", 2), CachedDay { version: 2, ..Default::default() });
+        assert_eq!(cache.get("day2/main/0", 1).part1, None);
+    }
+
+    #[test]
+    fn new_runs_replace_their_parts() {
+        let part = |answer: &str| CachedPart { answer: Some(answer.to_string()), time_ns: 10 };
+        let old = CachedDay { version: 1, parse_ns: 5, part1: Some(part("1")), part2: Some(part("2")) };
+        let result = DayResult {
+            parse_time: Duration::from_nanos(7),
+            part2: Some(PartResult { answer: Ok(Answer::Unsolved), time: Duration::from_nanos(3), memory: Default::default() }),
+            ..Default::default()
+        };
+        let new = old.updated(&result);
+        assert_eq!(new.part1, old.part1);
+        assert_eq!(new.part2, Some(CachedPart { answer: None, time_ns: 3 }));
+        assert_eq!(new.parse_ns, 7);
+        // Errors are not cached, so a part that fails keeps its earlier answer
+        let timeout = PartResult { answer: Err(Error::Timeout(Duration::from_secs(1))), time: Duration::from_secs(1), memory: Default::default() };
+        let result = DayResult { part1: Some(timeout), ..Default::default() };
+        assert_eq!(new.updated(&result).part1, new.part1);
+    }
+}
@@ -0,0 +1,310 @@
+use advent_of_code::error::Result as AocResult;
+use advent_of_code::export::Format;
+use advent_of_code::params::{Params, PARAMS_FILE};
+use advent_of_code::solution::Parts;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Command line interface for running the solutions
+#[derive(Parser, Debug)]
+#[command(about = "Advent of Code 2024 solutions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Log what the days are doing to stderr: `-v` for summaries, `-vv` for step by step dumps
+    #[arg(long, short, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Only log for these days, in the same form as the days to run (implies -v)
+    #[arg(long, value_name = "DAYS", global = true)]
+    pub log_days: Option<DaySelection>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run the solutions for the selected days
+    Run(RunArgs),
+    /// Add a new day from a template and register it with the runner
+    New(NewArgs),
+    /// Run every implementation of the selected days and check that they agree
+    Compare(CompareArgs),
+    /// Re-run a day's tests and parts whenever its source or input changes
+    Watch(WatchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CompareArgs {
+    /// Days to compare, in the same form as for `run`
+    #[arg(default_value = "all")]
+    pub days: DaySelection,
+
+    /// Read the input from this file, or from stdin if it is `-`
+    #[arg(long, short)]
+    pub input: Option<PathBuf>,
+
+    /// Only compare the given part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Seconds each implementation gets for each part before it is reported as timed out, 0 for no limit
+    #[arg(long, value_name = "SECONDS", default_value = "60", value_parser = parse_seconds)]
+    pub time_limit: Duration,
+
+    #[command(flatten)]
+    pub params: ParamArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Day to watch
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Only run the given part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Seconds each part gets before it is reported as timed out, 0 for no limit
+    #[arg(long, value_name = "SECONDS", default_value = "60", value_parser = parse_seconds)]
+    pub time_limit: Duration,
+
+    #[command(flatten)]
+    pub params: ParamArgs,
+}
+
+impl WatchArgs {
+    /// Arguments to pass on to `run`
+    pub fn run_args(&self) -> Vec<String> {
+        let mut args = vec!["--time-limit".to_string(), self.time_limit.as_secs_f64().to_string()];
+        if let Some(part) = self.part {
+            args.extend(["--part".to_string(), part.to_string()]);
+        }
+        if let Some(path) = &self.params.params {
+            args.extend(["--params".to_string(), path.display().to_string()]);
+        }
+        for assignment in &self.params.param {
+            args.extend(["--param".to_string(), assignment.clone()]);
+        }
+        return args;
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    /// Day to add
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Replace the day's module if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Days to run: `all`, a day (`6`), a range (`3..=9` or `3..9`) or a comma separated list of those
+    #[arg(default_value = "all")]
+    pub days: DaySelection,
+
+    /// Read the input from this file instead of `data/dayX.txt` and `data/dayX/*.txt`,
+    /// or from stdin if it is `-`. Only one day can be selected with this.
+    #[arg(long, short)]
+    pub input: Option<PathBuf>,
+
+    /// Run the days one after another instead of in parallel, which gives more reliable timings
+    #[arg(long)]
+    pub sequential: bool,
+
+    /// Write every answer, timing and status to this file, as JSON or CSV depending on its extension
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+
+    /// Format of the --export file, if it cannot be told from the extension
+    #[arg(long, requires = "export")]
+    pub format: Option<Format>,
+
+    /// Only run the given part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Run another implementation of the selected days instead of the main one, see `compare`
+    #[arg(long = "impl", value_name = "NAME")]
+    pub implementation: Option<String>,
+
+    /// Check the answers against the ones stored in `data/dayX.answers`
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Store answers for parts that have no stored answer yet (implies --verify)
+    #[arg(long)]
+    pub record: bool,
+
+    /// Solve every part again instead of using the answers cached by earlier runs
+    #[arg(long)]
+    pub force: bool,
+
+    /// Write pictures and animations of the days' maps to this directory, e.g. the guard's walk on day 6.
+    /// Every part is solved again, as cached answers draw nothing.
+    #[arg(long, value_name = "DIR")]
+    pub render: Option<PathBuf>,
+
+    /// Seconds each part gets before it is reported as timed out, 0 for no limit
+    #[arg(long, value_name = "SECONDS", default_value = "60", value_parser = parse_seconds)]
+    pub time_limit: Duration,
+
+    #[command(flatten)]
+    pub params: ParamArgs,
+}
+
+/// Where the puzzle parameters come from, see `advent_of_code::params`
+#[derive(Args, Debug)]
+pub struct ParamArgs {
+    /// Read the puzzle parameters from this file instead of `params.toml`
+    #[arg(long, value_name = "PATH")]
+    pub params: Option<PathBuf>,
+
+    /// Set a puzzle parameter, overriding the file, e.g. `--param day14.size=[11,7]`
+    #[arg(long, value_name = "dayN.NAME=VALUE")]
+    pub param: Vec<String>,
+}
+
+impl ParamArgs {
+    pub fn load(&self) -> AocResult<Params> {
+        let mut params = match &self.params {
+            Some(path) => Params::load(path, true)?,
+            None => Params::load(PARAMS_FILE.as_ref(), false)?,
+        };
+        for assignment in &self.param {
+            params.set(assignment)?;
+        }
+        return Ok(params);
+    }
+}
+
+pub fn parts(part: Option<u8>) -> Parts {
+    return match part {
+        Some(1) => Parts::Part1,
+        Some(2) => Parts::Part2,
+        _ => Parts::Both,
+    };
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|_| format!("Invalid number of seconds `{}`", s))?;
+    return Duration::try_from_secs_f64(secs).map_err(|_| format!("Invalid number of seconds `{}`", s));
+}
+
+/// Time limit for each part, where zero means there is none
+pub fn time_limit(limit: Duration) -> Option<Duration> {
+    return Some(limit).filter(|limit| !limit.is_zero());
+}
+
+/// Set of days selected on the command line, `None` meaning every day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaySelection(Option<Vec<usize>>);
+
+impl DaySelection {
+    /// Resolve the selection against the days that are available, keeping them in order
+    pub fn resolve(&self, num_days: usize) -> Result<Vec<usize>, String> {
+        let Some(days) = &self.0 else {
+            return Ok((1..=num_days).collect());
+        };
+        if let Some(day) = days.iter().find(|&&day| day > num_days) {
+            return Err(format!("Day {} is not implemented (days 1-{} are available)", day, num_days));
+        }
+        let mut days = days.clone();
+        days.sort();
+        days.dedup();
+        return Ok(days);
+    }
+}
+
+fn parse_day(day: &str) -> Result<usize, String> {
+    let day: usize = day.trim().parse().map_err(|_| format!("Invalid day `{}`", day))?;
+    if day == 0 || day > 25 {
+        return Err(format!("Day {} is out of range 1-25", day));
+    }
+    return Ok(day);
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Ok(DaySelection(None));
+        }
+        let mut days = Vec::new();
+        for item in s.split(',') {
+            let range = if let Some((lo, hi)) = item.split_once("..=") {
+                parse_day(lo)?..parse_day(hi)? + 1
+            } else if let Some((lo, hi)) = item.split_once("..") {
+                parse_day(lo)?..parse_day(hi)?
+            } else {
+                days.push(parse_day(item)?);
+                continue;
+            };
+            if range.is_empty() {
+                return Err(format!("Range `{}` does not contain any days", item.trim()));
+            }
+            days.extend(range);
+        }
+        if days.is_empty() {
+            return Err(format!("`{}` does not select any days", s));
+        }
+        return Ok(DaySelection(Some(days)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days(s: &str) -> Result<Vec<usize>, String> {
+        return s.parse::<DaySelection>()?.resolve(25);
+    }
+
+    #[test]
+    fn all_days() {
+        assert_eq!(days("all").unwrap(), (1..=25).collect::<Vec<_>>());
+        assert_eq!(days(" ALL ").unwrap(), (1..=25).collect::<Vec<_>>());
+        assert_eq!("all".parse::<DaySelection>().unwrap().resolve(21).unwrap().len(), 21);
+    }
+
+    #[test]
+    fn single_days_and_ranges() {
+        assert_eq!(days("6").unwrap(), vec![6]);
+        assert_eq!(days("3..9").unwrap(), (3..9).collect::<Vec<_>>());
+        assert_eq!(days("3..=9").unwrap(), (3..=9).collect::<Vec<_>>());
+        assert_eq!(days("5..=5").unwrap(), vec![5]);
+    }
+
+    #[test]
+    fn lists_are_sorted_and_deduplicated() {
+        assert_eq!(days("1,4,10..=12").unwrap(), vec![1, 4, 10, 11, 12]);
+        assert_eq!(days("12, 3..5, 4").unwrap(), vec![3, 4, 12]);
+    }
+
+    #[test]
+    fn out_of_range_days() {
+        assert!(days("0").is_err());
+        assert!(days("26").is_err());
+        assert!(days("20..=26").is_err());
+        assert!(days("six").is_err());
+        assert!(days("").is_err());
+        assert_eq!(
+            "22".parse::<DaySelection>().unwrap().resolve(21).unwrap_err(),
+            "Day 22 is not implemented (days 1-21 are available)"
+        );
+    }
+
+    #[test]
+    fn reversed_ranges() {
+        assert_eq!(days("1,9..3").unwrap_err(), "Range `9..3` does not contain any days");
+        assert_eq!(days("9..=3").unwrap_err(), "Range `9..=3` does not contain any days");
+        assert!(days("4..4").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::{Error, Result};
+use crate::params::DayParams;
+use crate::runner::{implementations, Implementation, MAIN};
+use crate::solution::{Answer, Parts};
+use crate::timing::format_duration;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// How one implementation did on one part
+pub struct Outcome {
+    pub name: &'static str,
+    // Fails if the implementation could not parse the input or solve the part
+    pub answer: Result<Answer>,
+    pub parse_time: Duration,
+    pub time: Duration,
+}
+
+// Run one part with an implementation that solves it
+fn run_part(implementation: &Implementation, contents: &str, params: &DayParams, part: u8, time_limit: Option<Duration>) -> Outcome {
+    let parts = if part == 1 { Parts::Part1 } else { Parts::Part2 };
+    let (answer, parse_time, time) = match implementation.runner.run(contents, params, parts, time_limit) {
+        Ok(result) => {
+            // Only the one part was run
+            let part_result = result.part1.or(result.part2).unwrap();
+            (part_result.answer, result.parse_time, part_result.time)
+        }
+        Err(e) => (Err(e), Duration::ZERO, Duration::ZERO),
+    };
+    return Outcome { name: implementation.name, answer, parse_time, time };
+}
+
+/// Run each implementation of a part of a day that has more than one, main first, giving each `time_limit`
+pub fn compare_part(day: usize, contents: &str, params: &DayParams, part: u8, time_limit: Option<Duration>) -> Vec<Outcome> {
+    let selected = if part == 1 { Parts::Part1 } else { Parts::Part2 };
+    let implementations = implementations(day).into_iter().filter(|i| i.parts.and(selected).is_some()).collect::<Vec<_>>();
+    if implementations.len() < 2 {
+        return vec![];
+    }
+    return implementations.iter().map(|i| run_part(i, contents, params, part, time_limit)).collect();
+}
+
+/// Run every implementation of the selected parts of a day on `contents` and write how their answers and
+/// speed compare to the main one to `out`. Returns how many disagree with the main one.
+pub fn compare_day(day: usize, contents: &str, params: &DayParams, parts: Parts, time_limit: Option<Duration>, out: &mut String) -> usize {
+    let mut num_different = 0;
+    let mut compared = false;
+    for part in [1, 2].into_iter().filter(|&p| if p == 1 { parts.part1() } else { parts.part2() }) {
+        let outcomes = compare_part(day, contents, params, part, time_limit);
+        let Some(main) = outcomes.first() else {
+            continue;
+        };
+        compared = true;
+        let show = |outcome: &Outcome| match &outcome.answer {
+            Ok(answer) => answer.to_string(),
+            Err(e @ Error::Timeout(_)) => e.to_string(),
+            Err(e) => format!("failed: {}", e),
+        };
+        let name_width = outcomes.iter().map(|o| o.name.len()).max().unwrap();
+        let answer_width = outcomes.iter().map(|o| show(o).chars().count()).max().unwrap();
+        writeln!(out, "Part {}:", part).unwrap();
+        for outcome in &outcomes {
+            let same = match (&outcome.answer, &main.answer) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            };
+            let verdict = if outcome.name == MAIN {
+                String::new()
+            } else if same {
+                format!("agrees, {:.2}x the time of {}", outcome.time.as_secs_f64() / main.time.as_secs_f64(), MAIN)
+            } else {
+                num_different += 1;
+                "DISAGREES".to_string()
+            };
+            let line = format!(
+                "  {:<name_width$}  {:<answer_width$}  parse {:>10}, part {:>10}  {}",
+                outcome.name,
+                show(outcome),
+                format_duration(outcome.parse_time),
+                format_duration(outcome.time),
+                verdict
+            );
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+    }
+    if !compared {
+        writeln!(out, "Only the {} implementation solves the selected parts", MAIN).unwrap();
+    }
+    return num_different;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_day12_agrees_on_small_example() {
+        let mut out = String::new();
+        assert_eq!(compare_day(12, "AAAA\nBBCD\nBBCC\nEEEC\n", &DayParams::default(), Parts::Both, None, &mut out), 0);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Part 1:");
+        assert!(lines[1].starts_with("  main  140  parse"));
+        assert!(lines[2].starts_with("  old   140  parse") && lines[2].contains("agrees"));
+    }
+
+    #[test]
+    fn days_without_alternatives() {
+        assert!(compare_part(1, "1 2\n", &DayParams::default(), 1, None).is_empty());
+        let mut out = String::new();
+        assert_eq!(compare_day(17, "", &DayParams::default(), Parts::Part1, None, &mut out), 0);
+        assert_eq!(out, "Only the main implementation solves the selected parts\n");
+    }
+}
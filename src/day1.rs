@@ -1,20 +1,27 @@
+#![allow(clippy::ptr_arg)]
+
+use crate::error::{parse_at, Error, Result};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 use std::iter::zip;
 
 // Parse the input as two columns of integers and return two vectors,
 // one for each column.
-fn parse_input(contents: &String) -> (Vec<i32>, Vec<i32>) {
+fn parse_input(contents: &str) -> Result<(Vec<i32>, Vec<i32>)> {
     // Parse the input as two columns of integers
-    let re = Regex::new(r"(\d+)\s+(\d+)").unwrap();
+    let re = Regex::new(r"^\s*(\d+)\s+(\d+)\s*$").unwrap();
     // Initialize two vectors to store the columns
     let mut col1 = Vec::new();
     let mut col2 = Vec::new();
-    // Iterate over the captures and store them in the vectors
-    for cap in re.captures_iter(contents) {
-        col1.push(cap[1].parse().unwrap());
-        col2.push(cap[2].parse().unwrap());
+    // Match each line and store the columns in the vectors
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let Some(cap) = re.captures(line) else {
+            return Err(Error::parse_at(contents, line, "Expected two columns of integers"));
+        };
+        col1.push(parse_at(contents, cap.get(1).unwrap().as_str())?);
+        col2.push(parse_at(contents, cap.get(2).unwrap().as_str())?);
     }
-    return (col1, col2);
+    return Ok((col1, col2));
 }
 
 // Function that takes sorted vectors as input
@@ -48,19 +55,59 @@ fn part2(v1: &Vec<i32>, v2: &Vec<i32>) -> i64 {
     return score;
 }
 
-pub fn fcn(contents: &String) {
-    // Parse the input
-    let (mut v1, mut v2) = parse_input(contents);
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = (Vec<i32>, Vec<i32>);
 
-    // Sort the vectors
-    v1.sort();
-    v2.sort();
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        // Parse the input
+        let (mut v1, mut v2) = parse_input(contents)?;
+
+        // Sort the vectors
+        v1.sort();
+        v2.sort();
+        return Ok((v1, v2));
+    }
 
     // Compute the Wasserstein-1 distance
-    let p1_ans = part1(&v1, &v2);
-    println!("Part 1: {}", p1_ans);
+    fn part1(&self, (v1, v2): &Self::Input) -> Result<Answer> {
+        return Ok(part1(v1, v2).into());
+    }
 
     // Compute the Similarity score
-    let p2_ans = part2(&v1, &v2);
-    println!("Part 2: {}", p2_ans);
-}
\ No newline at end of file
+    fn part2(&self, (v1, v2): &Self::Input) -> Result<Answer> {
+        return Ok(part2(v1, v2).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day1.parse(EXAMPLE).unwrap();
+        assert_eq!(Day1.part1(&input).unwrap().to_string(), "11");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day1.parse(EXAMPLE).unwrap();
+        assert_eq!(Day1.part2(&input).unwrap().to_string(), "31");
+    }
+
+    #[test]
+    fn invalid_line() {
+        let err = Day1.parse("3   4\n4   x\n").unwrap_err();
+        assert_eq!(err.to_string(), "Parse error at line 2, column 1: Expected two columns of integers");
+    }
+}
@@ -1,76 +1,93 @@
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::solution::{Answer, Solution};
 use std::collections::HashSet;
 
 // Given a string of integers 0-9 separated by newlines
-// Create a matrix
-fn parse_contents(contents: &String) -> Vec<Vec<u8>> {
-    let mut matrix: Vec<Vec<u8>> = Vec::new();
-    for line in contents.lines() {
-        let mut row: Vec<u8> = Vec::new();
-        for c in line.chars() {
-            if c == '.' {
-                row.push(u8::MAX);
-            } else {
-                row.push(c.to_digit(10).unwrap() as u8);
-            }
-        }
-        matrix.push(row);
-    }
-    return matrix;
-}
-
-#[derive(Clone, Copy, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-    None,
+// Create a matrix, where `.` is a tile that cannot be climbed
+fn parse_contents(contents: &str) -> Result<Grid<u8>> {
+    return Grid::parse(contents, "height", |c| if c == '.' { Some(u8::MAX) } else { c.to_digit(10).map(|h| h as u8) });
 }
 
-fn find_trailheads_helper(matrix: &Vec<Vec<u8>>, i: usize, j: usize, from: Direction, curr_level: u8, curr_reachable: &mut HashSet<(usize,usize)>) -> usize {
-    if matrix[i][j] != curr_level {
+// Heights only go up along a trail, so it never turns back on itself
+fn find_trailheads_helper(matrix: &Grid<u8>, pos: (usize, usize), curr_level: u8, curr_reachable: &mut HashSet<(usize,usize)>) -> usize {
+    if matrix[pos] != curr_level {
         return 0;
     }
     if curr_level == 9 {
-        curr_reachable.insert((i,j));
+        curr_reachable.insert(pos);
         return 1;
     }
     let mut rating = 0;
-    if i > 0 && from != Direction::Down {
-        rating += find_trailheads_helper(matrix, i - 1, j, Direction::Up, curr_level + 1, curr_reachable);
-    }
-    if i < matrix.len() - 1 && from != Direction::Up {
-        rating += find_trailheads_helper(matrix, i + 1, j, Direction::Down, curr_level + 1, curr_reachable);
-    }
-    if j > 0 && from != Direction::Left {
-        rating += find_trailheads_helper(matrix, i, j - 1, Direction::Right, curr_level + 1, curr_reachable);
-    }
-    if j < matrix[i].len() - 1 && from != Direction::Right {
-        rating += find_trailheads_helper(matrix, i, j + 1, Direction::Left, curr_level + 1, curr_reachable);
+    for next in matrix.neighbors4(pos) {
+        rating += find_trailheads_helper(matrix, next, curr_level + 1, curr_reachable);
     }
     return rating;
 }
 
-fn both_parts(matrix: &Vec<Vec<u8>>) -> (usize,usize) {
+fn both_parts(matrix: &Grid<u8>) -> (usize,usize) {
     let mut count = 0;
     let mut rating = 0;
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            if matrix[i][j] == 0 {
-                let mut reachable_ij: HashSet<(usize,usize)> = HashSet::new();
-                let ij_rating = find_trailheads_helper(matrix, i, j, Direction::None, 0, &mut reachable_ij);
-                let ij_count = reachable_ij.len();
-                count += ij_count;
-                rating += ij_rating;
-            }
+    for (pos, &height) in matrix.iter() {
+        if height == 0 {
+            let mut reachable: HashSet<(usize,usize)> = HashSet::new();
+            let pos_rating = find_trailheads_helper(matrix, pos, 0, &mut reachable);
+            count += reachable.len();
+            rating += pos_rating;
         }
     }
     return (count, rating);
 }
 
-pub fn fcn(contents: &String) {
-    let matrix = parse_contents(contents);
-    let (p1, p2) = both_parts(&matrix);
-    println!("Part 1: {}", p1);
-    println!("Part 2: {}", p2);
-}
\ No newline at end of file
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Grid<u8>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, matrix: &Self::Input) -> Result<Answer> {
+        let (count, _) = both_parts(matrix);
+        return Ok(count.into());
+    }
+
+    fn part2(&self, matrix: &Self::Input) -> Result<Answer> {
+        let (_, rating) = both_parts(matrix);
+        return Ok(rating.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day10.parse(EXAMPLE).unwrap();
+        assert_eq!(Day10.part1(&input).unwrap().to_string(), "36");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day10.parse(EXAMPLE).unwrap();
+        assert_eq!(Day10.part2(&input).unwrap().to_string(), "81");
+    }
+
+    #[test]
+    fn impassable_tiles() {
+        let input = Day10.parse("...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9").unwrap();
+        assert_eq!(Day10.part1(&input).unwrap().to_string(), "2");
+    }
+}
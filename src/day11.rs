@@ -1,8 +1,27 @@
-// use indicatif::ProgressBar;
+#![allow(clippy::ptr_arg)]
+
+use crate::error::{parse_at, Result};
+use crate::params::DayParams;
+use crate::solution::{Answer, Solution};
+use serde::Deserialize;
 use std::collections::HashMap;
 
-fn parse_contents(contents: &String) -> Vec<usize> {
-    return contents.split(" ").map(|x| x.parse::<usize>().unwrap()).collect::<Vec<usize>>();
+/// How many times the stones blink in each part
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub part1_blinks: u8,
+    pub part2_blinks: u8,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        return Params { part1_blinks: 25, part2_blinks: 75 };
+    }
+}
+
+fn parse_contents(contents: &str) -> Result<Vec<usize>> {
+    return contents.split(" ").map(|x| parse_at(contents, x)).collect::<Result<Vec<usize>>>();
 }
 
 #[inline]
@@ -27,7 +46,8 @@ fn split_digits(num: usize, num_digits: u8) -> (usize, usize) {
     return (left, right);
 }
 
-fn recursive_soln(num: usize, num_iter: u8, cache: &mut HashMap<(u8, usize), usize>) -> usize {
+/// Number of stones `num` turns into after `num_iter` blinks, caching the counts by (blinks, number)
+pub fn recursive_soln(num: usize, num_iter: u8, cache: &mut HashMap<(u8, usize), usize>) -> usize {
     if cache.contains_key(&(num_iter, num)) {
         return *cache.get(&(num_iter, num)).unwrap();
     }
@@ -41,7 +61,7 @@ fn recursive_soln(num: usize, num_iter: u8, cache: &mut HashMap<(u8, usize), usi
         return soln;
     }
     let d = get_num_digits(num);
-    if d % 2 == 0 {
+    if d.is_multiple_of(2) {
         let (num1, num2) = split_digits(num, d);
         let soln = recursive_soln(num1, num_iter - 1, cache) + recursive_soln(num2, num_iter - 1, cache);
         cache.insert((num_iter, num), soln);
@@ -58,10 +78,52 @@ fn both_parts_recursive(line: &Vec<usize>, num_iter: u8) -> usize {
     return line.iter().map(|x| recursive_soln(*x, num_iter, &mut cache)).sum();
 }
 
-pub fn fcn(contents: &String) {
-    let line = parse_contents(contents);
-    let p1 = both_parts_recursive(&line, 25);
-    println!("\nPart 1: {}", p1);
-    let p2 = both_parts_recursive(&line, 75);
-    println!("Part 2: {}", p2);
-}
\ No newline at end of file
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = (Params, Vec<usize>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return self.parse_with(contents, &DayParams::default());
+    }
+
+    fn parse_with(&self, contents: &str, params: &DayParams) -> Result<Self::Input> {
+        return Ok((params.get()?, parse_contents(contents)?));
+    }
+
+    fn part1(&self, (params, line): &Self::Input) -> Result<Answer> {
+        return Ok(both_parts_recursive(line, params.part1_blinks).into());
+    }
+
+    fn part2(&self, (params, line): &Self::Input) -> Result<Answer> {
+        return Ok(both_parts_recursive(line, params.part2_blinks).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "125 17";
+
+    #[test]
+    fn example_part1() {
+        let input = Day11.parse(EXAMPLE).unwrap();
+        assert_eq!(Day11.part1(&input).unwrap().to_string(), "55312");
+    }
+
+    #[test]
+    fn example_six_blinks() {
+        let input = Day11.parse_with(EXAMPLE, &DayParams::from_toml("part1_blinks = 6").unwrap()).unwrap();
+        assert_eq!(Day11.part1(&input).unwrap().to_string(), "22");
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(get_num_digits(7), 1);
+        assert_eq!(get_num_digits(2024), 4);
+        assert_eq!(split_digits(2024, 4), (20, 24));
+        assert_eq!(split_digits(1000, 4), (10, 0));
+        assert_eq!(split_digits(99, 2), (9, 9));
+    }
+}
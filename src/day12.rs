@@ -1,74 +1,43 @@
+use crate::error::Result;
+use crate::geometry::Direction;
+use crate::grid::Grid;
+use crate::solution::{Answer, Solution};
 use std::collections::{HashSet, HashMap};
 // Parse the contents as a matrix of characters
 // Also return all unique characters in the matrix
-fn parse_contents(contents: &String) -> (Vec<Vec<char>>, Vec<char>) {
-    let mut unique_chars: HashSet<char> = HashSet::new();
-    let grid = contents.lines().map(|x| {
-        let ret = x.chars().collect::<Vec<char>>();
-        for c in ret.iter() {
-            unique_chars.insert(*c);
-        }
-        return ret;
-    }).collect();
-    return (grid, unique_chars.into_iter().collect());
-}
-
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    North,
-    South,
-    East,
-    West
-}
-
-fn check_next_pos(pos: (usize, usize), dir: Direction, maxes: (usize, usize)) -> Option<(usize, usize)> {
-    let (max0, max1) = maxes;
-    match dir {
-        Direction::North  => {if pos.0 >    0 {Some((pos.0-1,pos.1  ))} else {None}},
-        Direction::South  => {if pos.0 < max0 {Some((pos.0+1,pos.1  ))} else {None}},
-        Direction::East   => {if pos.1 >    0 {Some((pos.0  ,pos.1-1))} else {None}},
-        Direction::West   => {if pos.1 < max1 {Some((pos.0  ,pos.1+1))} else {None}},
-    }
+fn parse_contents(contents: &str) -> Result<(Grid<char>, Vec<char>)> {
+    let grid = Grid::parse_chars(contents)?;
+    let unique_chars: HashSet<char> = grid.iter().map(|(_, &c)| c).collect();
+    return Ok((grid, unique_chars.into_iter().collect()));
 }
 
 // Return how much to add to perimeter
-fn explore_direction_old(grid: &Vec<Vec<char>>, visited: &Vec<Vec<bool>>, c: char, pos: (usize, usize), dir: Direction, stacks: &mut HashMap<char, Vec<(usize, usize)>>) -> usize {
-    let maxes = (grid.len() - 1, grid[pos.0].len()-1);
-    let next_pos_opt = check_next_pos(pos, dir, maxes);
-    if next_pos_opt.is_none() {
+fn explore_direction_old(grid: &Grid<char>, visited: &Grid<bool>, c: char, pos: (usize, usize), dir: Direction, stacks: &mut HashMap<char, Vec<(usize, usize)>>) -> usize {
+    let Some(next_pos) = grid.step(pos, dir.offset()) else {
         return 1;
-    }
-    let next_pos = next_pos_opt.unwrap();
+    };
 
-    let c_next = grid[next_pos.0][next_pos.1];
-    if !visited[next_pos.0][next_pos.1] {
+    let c_next = grid[next_pos];
+    if !visited[next_pos] {
         let next_stack = stacks.get_mut(&c_next).unwrap();
         next_stack.push(next_pos);
     }
-    let p_ret = (c_next != c) as usize;
-    // print!(", {:?}'{}'({})", dir, c_next, p_ret);
-    return p_ret
+    return (c_next != c) as usize;
 }
 
-fn explore_tile_old(grid: &Vec<Vec<char>>, visited: &Vec<Vec<bool>>, c: char, pos: (usize, usize), stacks: &mut HashMap<char, Vec<(usize, usize)>>) -> usize {
+fn explore_tile_old(grid: &Grid<char>, visited: &Grid<bool>, c: char, pos: (usize, usize), stacks: &mut HashMap<char, Vec<(usize, usize)>>) -> usize {
     // Explore in all four directions (or as many as possible).
     // In every direction that has a different character, add one to the perimeter
-    // print!("{}: ({},{})", c, pos.0, pos.1);
-    let east_p = explore_direction_old(grid, visited, c, pos, Direction::East, stacks);
-    let west_p = explore_direction_old(grid, visited, c, pos, Direction::West, stacks);
-    let north_p = explore_direction_old(grid, visited, c, pos, Direction::North, stacks);
-    let south_p = explore_direction_old(grid, visited, c, pos, Direction::South, stacks);
-    // println!("E{}, W{}, N{}, S{}", c, pos.0, pos.1, east_p, west_p, north_p, south_p);
-    // print!("\n");
-    return east_p + west_p + north_p + south_p;
+    let perims = Direction::ALL.map(|dir| explore_direction_old(grid, visited, c, pos, dir, stacks));
+    log::trace!("{}: ({},{}) N{}, E{}, S{}, W{}", c, pos.0, pos.1, perims[0], perims[1], perims[2], perims[3]);
+    return perims.iter().sum();
 }
 
-#[allow(dead_code)]
 fn find_first_stack(stacks: &HashMap<char, Vec<(usize, usize)>>) -> char {
     // Find first stack that has elements
     let mut which_stack = '.';
     for (k, v) in stacks.iter() {
-        if v.len() > 0 {
+        if !v.is_empty() {
             which_stack = *k;
             break;
         }
@@ -76,16 +45,15 @@ fn find_first_stack(stacks: &HashMap<char, Vec<(usize, usize)>>) -> char {
     return which_stack
 }
 
-#[allow(dead_code)]
-fn part1_old(grid: &Vec<Vec<char>>, unique_chars: &Vec<char>) -> usize {
-    let mut visited: Vec<Vec<bool>> = grid.iter().map(|x| x.iter().map(|_| false).collect::<Vec<bool>>()).collect();
+fn part1_old(grid: &Grid<char>, unique_chars: &Vec<char>) -> usize {
+    let mut visited = grid.map(|_| false);
     let mut stacks: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
     let mut area_perims: HashMap<char, (usize, usize)> = HashMap::new();
     for c in unique_chars {
         stacks.insert(*c, vec![]);
         area_perims.insert(*c, (0,0));
     }
-    let first_char = grid[0][0];
+    let first_char = grid[(0, 0)];
     stacks.get_mut(&first_char).unwrap().push((0,0));
     loop {
         let which_stack = find_first_stack(&stacks);
@@ -94,44 +62,29 @@ fn part1_old(grid: &Vec<Vec<char>>, unique_chars: &Vec<char>) -> usize {
         } else {
             let stack = stacks.get_mut(&which_stack).unwrap();
             let next_pos = stack.pop().unwrap();
-            if !visited[next_pos.0][next_pos.1] {
+            if !visited[next_pos] {
                 let area_perim = area_perims.get_mut(&which_stack).unwrap();
                 let add_perim = explore_tile_old(grid, &visited, which_stack, next_pos, &mut stacks);
                 area_perim.0 += 1;
                 area_perim.1 += add_perim;
-                visited[next_pos.0][next_pos.1] = true;
+                visited[next_pos] = true;
             }
         }
     }
     let ret: usize = area_perims.iter().map(|ap| ap.1.0 * ap.1.1).sum();
     for (k,v) in area_perims {
-        println!("{}: {:?}", k, v);
+        log::debug!("{}: area {}, perimeter {}", k, v.0, v.1);
     }
     return ret;
 }
 
-fn find_first_unvisited(visited: &Vec<Vec<bool>>) -> Option<(usize, usize)> {
-    let (n_i,n_j) = (visited.len(), visited[0].len());
-    for i in 0..n_i {
-        for j in 0..n_j {
-            if !visited[i][j] {
-                return Some((i,j));
-            }
-        }
-    }
-    return None;
-}
-
 // Return how much to add to perimeter
-fn explore_direction(grid: &Vec<Vec<char>>, visited: &mut Vec<Vec<bool>>, c: char, pos: (usize, usize), dir: Direction, area:  &mut usize, perim: &mut usize) {
-    let maxes = (grid.len() - 1, grid[pos.0].len()-1);
-    let next_pos_opt = check_next_pos(pos, dir, maxes);
-    if next_pos_opt.is_none() {
+fn explore_direction(grid: &Grid<char>, visited: &mut Grid<bool>, c: char, pos: (usize, usize), dir: Direction, area:  &mut usize, perim: &mut usize) {
+    let Some(next_pos) = grid.step(pos, dir.offset()) else {
         *perim += 1;
         return;
-    }
-    let next_pos = next_pos_opt.unwrap();
-    let c_next = grid[next_pos.0][next_pos.1];
+    };
+    let c_next = grid[next_pos];
     if c_next == c {
         visit_helper(grid, visited, next_pos, area, perim);
     } else {
@@ -140,46 +93,100 @@ fn explore_direction(grid: &Vec<Vec<char>>, visited: &mut Vec<Vec<bool>>, c: cha
     }
 }
 
-fn explore_tile(grid: &Vec<Vec<char>>, visited: &mut Vec<Vec<bool>>, c: char, pos: (usize, usize), area: &mut usize, perim: &mut usize) {
+fn explore_tile(grid: &Grid<char>, visited: &mut Grid<bool>, c: char, pos: (usize, usize), area: &mut usize, perim: &mut usize) {
     // Explore in all four directions (or as many as possible).
     // In every direction that has a different character, add one to the perimeter
     *area += 1;
-    explore_direction(grid, visited, c, pos, Direction::East, area, perim);
-    explore_direction(grid, visited, c, pos, Direction::West, area, perim);
-    explore_direction(grid, visited, c, pos, Direction::North, area, perim);
-    explore_direction(grid, visited, c, pos, Direction::South, area, perim);
+    for dir in Direction::ALL {
+        explore_direction(grid, visited, c, pos, dir, area, perim);
+    }
 }
 
-fn visit_helper(grid: &Vec<Vec<char>>, visited: &mut Vec<Vec<bool>>, pos: (usize, usize), area: &mut usize, perim: &mut usize) {
-    if visited[pos.0][pos.1] {
+fn visit_helper(grid: &Grid<char>, visited: &mut Grid<bool>, pos: (usize, usize), area: &mut usize, perim: &mut usize) {
+    if visited[pos] {
         return;
     }
-    let c = grid[pos.0][pos.1];
-    visited[pos.0][pos.1] = true;
+    let c = grid[pos];
+    visited[pos] = true;
     explore_tile(grid, visited, c, pos, area, perim);
 }
 
-fn part1(grid: &Vec<Vec<char>>, _unique: &Vec<char>) -> usize {
-    let mut visited: Vec<Vec<bool>> = grid.iter().map(|x| x.iter().map(|_| false).collect::<Vec<bool>>()).collect();
+fn part1(grid: &Grid<char>, _unique: &Vec<char>) -> usize {
+    let mut visited = grid.map(|_| false);
     let mut cost = 0;
-    loop {
-        let first_unvisited = find_first_unvisited(&visited);
-        if first_unvisited.is_none() {
-            break;
-        }
-        let pos = first_unvisited.unwrap();
+    while let Some(pos) = visited.position(|&v| !v) {
         let mut area = 0;
         let mut perim = 0;
         visit_helper(grid, &mut visited, pos, &mut area, &mut perim);
-        // println!("Char {}, Start ({},{}), Area: {}, Perimeter: {}", grid[pos.0][pos.1], pos.0, pos.1, area, perim);
+        log::trace!("Char {}, Start ({},{}), Area: {}, Perimeter: {}", grid[pos], pos.0, pos.1, area, perim);
         cost += area * perim;
     }
     return cost;
 }
 
-pub fn fcn(contents: &String) {
-    // Parse the input
-    let (grid, chars) = parse_contents(contents);
-    let p1 = part1(&grid, &chars);
-    println!("Part 1: {}", p1);
-}
\ No newline at end of file
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = (Grid<char>, Vec<char>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, (grid, chars): &Self::Input) -> Result<Answer> {
+        return Ok(part1(grid, chars).into());
+    }
+}
+
+// First attempt at part 1, keeping a stack of tiles to visit per plant
+pub struct Day12Old;
+
+impl Solution for Day12Old {
+    type Input = (Grid<char>, Vec<char>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, (grid, chars): &Self::Input) -> Result<Answer> {
+        return Ok(part1_old(grid, chars).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_SMALL: &str = "AAAA
+BBCD
+BBCC
+EEEC
+";
+
+    const EXAMPLE_NESTED: &str = "OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO
+";
+
+    const EXAMPLE_LARGE: &str = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE
+";
+
+    #[test]
+    fn example_part1() {
+        for (example, expected) in [(EXAMPLE_SMALL, "140"), (EXAMPLE_NESTED, "772"), (EXAMPLE_LARGE, "1930")] {
+            let input = Day12.parse(example).unwrap();
+            assert_eq!(Day12.part1(&input).unwrap().to_string(), expected);
+        }
+    }
+}
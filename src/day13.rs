@@ -1,4 +1,23 @@
-use regex;
+#![allow(clippy::ptr_arg, clippy::type_complexity)]
+
+use crate::error::{parse_at, Error, Result};
+use crate::params::DayParams;
+use crate::solution::{Answer, Solution};
+use serde::Deserialize;
+
+/// Tokens it costs to press buttons A and B, and how far the prizes are moved for part 2
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub costs: (usize, usize),
+    pub offset: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        return Params { costs: (3, 1), offset: 10000000000000 };
+    }
+}
 
 // Use static arrays
 fn matrix_solve2x2(matrix: [[f64; 2]; 2], vec: [f64; 2]) -> [f64; 2] {
@@ -8,30 +27,32 @@ fn matrix_solve2x2(matrix: [[f64; 2]; 2], vec: [f64; 2]) -> [f64; 2] {
     return [ret[0]/det, ret[1]/det];
 }
 
-fn get_block(block: &str, offset: usize) -> ([[f64; 2]; 2], [f64; 2]) {
+// Match `line`, a slice of `contents`, against `pattern` and parse the two numbers it captures
+fn get_pair(contents: &str, line: Option<&str>, pattern: &str) -> Result<[f64; 2]> {
+    let re = regex::Regex::new(pattern).unwrap();
+    let Some(line) = line else {
+        return Err(Error::invalid(format!("Claw machine is missing a line matching `{}`", pattern)));
+    };
+    let Some(cap) = re.captures(line) else {
+        return Err(Error::parse_at(contents, line, format!("Expected a line matching `{}`", pattern)));
+    };
+    return Ok([parse_at(contents, cap.get(1).unwrap().as_str())?, parse_at(contents, cap.get(2).unwrap().as_str())?]);
+}
+
+fn get_block(contents: &str, block: &str) -> Result<([[f64; 2]; 2], [f64; 2])> {
     // Block formatting:
     //  Button A: X+M[0][0], Y+M[1][0]
     // Button B: X+M[0][1], Y+M[1][1]
     // Prize: X=b[0], Y=b[1]
-    let lines = block.lines().collect::<Vec<&str>>();
+    let mut lines = block.lines();
     let mut matrix = [[0.0; 2]; 2];
-    let mut vec = [0.0; 2];
     // Match first line with regex
-    let re = regex::Regex::new(r"Button A: X\+([0-9]+), Y\+([0-9]+)").unwrap();
-    let cap = re.captures(lines[0]).unwrap();
-    matrix[0][0] = cap[1].parse::<f64>().unwrap();
-    matrix[1][0] = cap[2].parse::<f64>().unwrap();
+    [matrix[0][0], matrix[1][0]] = get_pair(contents, lines.next(), r"Button A: X\+([0-9]+), Y\+([0-9]+)")?;
     // Match second line with regex
-    let re = regex::Regex::new(r"Button B: X\+([0-9]+), Y\+([0-9]+)").unwrap();
-    let cap = re.captures(lines[1]).unwrap();
-    matrix[0][1] = cap[1].parse::<f64>().unwrap();
-    matrix[1][1] = cap[2].parse::<f64>().unwrap();
+    [matrix[0][1], matrix[1][1]] = get_pair(contents, lines.next(), r"Button B: X\+([0-9]+), Y\+([0-9]+)")?;
     // Match third line with regex
-    let re = regex::Regex::new(r"Prize: X=([0-9]+), Y=([0-9]+)").unwrap();
-    let cap = re.captures(lines[2]).unwrap();
-    vec[0] = cap[1].parse::<f64>().unwrap() + offset as f64;
-    vec[1] = cap[2].parse::<f64>().unwrap() + offset as f64;
-    return (matrix, vec);
+    let vec = get_pair(contents, lines.next(), r"Prize: X=([0-9]+), Y=([0-9]+)")?;
+    return Ok((matrix, vec));
 }
 
 fn check_valid_soln(soln: [f64; 2]) -> bool {
@@ -45,26 +66,94 @@ fn check_valid_soln(soln: [f64; 2]) -> bool {
     return true;
 }
 
-fn solve_block(block: &str, cost: (usize, usize), offset: usize) -> usize {
-    let (matrix, vec) = get_block(block, offset);
+fn solve_block(block: &([[f64; 2]; 2], [f64; 2]), cost: (usize, usize), offset: usize) -> usize {
+    let (matrix, mut vec) = *block;
+    vec[0] += offset as f64;
+    vec[1] += offset as f64;
     let solution = matrix_solve2x2(matrix, vec);
     let soln_cost = cost.0 * solution[0] as usize + cost.1 * solution[1] as usize;
     let is_valid = check_valid_soln(solution);
     return (is_valid as usize) * soln_cost;
 }
 
-fn parse_contents(contents: &String, cost: (usize, usize), offset: usize) -> Vec<usize> {
+fn parse_contents(contents: &str) -> Result<Vec<([[f64; 2]; 2], [f64; 2])>> {
     // Split contents by double newline
-    let mut ret: Vec<usize> = Vec::new();
-    for block in contents.split("\n\n") {
-        ret.push(solve_block(block, cost, offset));
+    return contents.split("\n\n").map(|block| get_block(contents, block)).collect();
+}
+
+fn total_cost(blocks: &Vec<([[f64; 2]; 2], [f64; 2])>, cost: (usize, usize), offset: usize) -> usize {
+    return blocks.iter().map(|block| solve_block(block, cost, offset)).sum();
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = (Params, Vec<([[f64; 2]; 2], [f64; 2])>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return self.parse_with(contents, &DayParams::default());
+    }
+
+    fn parse_with(&self, contents: &str, params: &DayParams) -> Result<Self::Input> {
+        return Ok((params.get()?, parse_contents(contents)?));
+    }
+
+    fn part1(&self, (params, blocks): &Self::Input) -> Result<Answer> {
+        return Ok(total_cost(blocks, params.costs, 0).into());
+    }
+
+    fn part2(&self, (params, blocks): &Self::Input) -> Result<Answer> {
+        return Ok(total_cost(blocks, params.costs, params.offset).into());
     }
-    return ret;
 }
 
-pub fn fcn(contents: &String) {
-    let p1_sol = parse_contents(contents, (3, 1), 0);
-    println!("Part 1: {}", p1_sol.iter().sum::<usize>());
-    let p2_sol = parse_contents(contents, (3, 1), 10000000000000);
-    println!("Part 2: {}", p2_sol.iter().sum::<usize>());
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day13.parse(EXAMPLE).unwrap();
+        assert_eq!(Day13.part1(&input).unwrap().to_string(), "480");
+    }
+
+    #[test]
+    fn example_with_crlf() {
+        let contents = crate::input::normalize(&EXAMPLE.replace('\n', "\r\n"));
+        let input = Day13.parse(&contents).unwrap();
+        assert_eq!(Day13.part1(&input).unwrap().to_string(), "480");
+    }
+
+    #[test]
+    fn example_part2_winnable() {
+        // Only the second and fourth machines can win the prize with the offset
+        let (params, blocks) = Day13.parse(EXAMPLE).unwrap();
+        let winnable: Vec<bool> = blocks.iter().map(|block| solve_block(block, params.costs, params.offset) > 0).collect();
+        assert_eq!(winnable, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn solve_2x2() {
+        let soln = matrix_solve2x2([[94.0, 22.0], [34.0, 67.0]], [8400.0, 5400.0]);
+        assert_eq!(soln, [80.0, 40.0]);
+        assert!(check_valid_soln(soln));
+        assert!(!check_valid_soln(matrix_solve2x2([[26.0, 67.0], [66.0, 21.0]], [12748.0, 12176.0])));
+    }
+}
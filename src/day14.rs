@@ -1,6 +1,27 @@
-use regex;
+#![allow(clippy::ptr_arg, clippy::type_complexity)]
+
+use crate::budget;
+use crate::error::{parse_at, Error, Result};
+use crate::grid::Grid;
+use crate::params::DayParams;
+use crate::render::{self, Animation};
+use crate::solution::{Answer, Solution};
+use serde::Deserialize;
 use std::collections::HashSet;
-use image::{RgbImage, Rgb};
+
+/// Size of the room and how many steps part 1 looks ahead, which are smaller for the example
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub size: (usize, usize),
+    pub steps: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        return Params { size: (101, 103), steps: 100 };
+    }
+}
 
 fn math_mod(x: i64, m: usize) -> usize {
     // This function is used to correct the negative modulus of a number
@@ -9,30 +30,54 @@ fn math_mod(x: i64, m: usize) -> usize {
     return ret
 }
 
-fn parse_contents(contents: &String) -> (Vec<(usize, usize)>,Vec<(i64, i64)>) {
+fn parse_contents(contents: &str) -> Result<(Vec<(usize, usize)>,Vec<(i64, i64)>)> {
     let mut positions = Vec::new();
     let mut velocities = Vec::new();
+    let re = regex::Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
     for line in contents.lines() {
-        let re = regex::Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
-        let cap = re.captures(line).unwrap();
-        let p: (usize, usize) = (cap[1].parse().unwrap(), cap[2].parse().unwrap());
-        let v: (i64, i64) = (cap[3].parse().unwrap(), cap[4].parse().unwrap());
+        let Some(cap) = re.captures(line) else {
+            return Err(Error::parse_at(contents, line, "Expected a robot `p=x,y v=dx,dy`"));
+        };
+        let field = |i: usize| cap.get(i).unwrap().as_str();
+        let p: (usize, usize) = (parse_at(contents, field(1))?, parse_at(contents, field(2))?);
+        let v: (i64, i64) = (parse_at(contents, field(3))?, parse_at(contents, field(4))?);
         positions.push(p);
         velocities.push(v);
     }
-    return (positions, velocities);
+    return Ok((positions, velocities));
 }
 
-fn part1(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (usize, usize)) -> usize {
-    let num_steps = 100;
-    let traj = positions.iter().zip(velocities.iter()).map(
+fn positions_after(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (usize, usize), num_steps: usize) -> Vec<(usize, usize)> {
+    return positions.iter().zip(velocities.iter()).map(
         |(p, v)| {
             let traj0 = math_mod(p.0 as i64 + v.0*(num_steps as i64), size.0);
             let traj1 = math_mod(p.1 as i64 + v.1*(num_steps as i64), size.1);
             return (traj0, traj1);
         })
         .collect::<Vec<(usize, usize)>>();
-    // Get positions of all robots after 100 steps
+}
+
+// Map of the room with a cell set wherever there is a robot. Positions are (x, y), with y going down the rows.
+fn room(positions: &Vec<(usize, usize)>, size: (usize, usize)) -> Grid<bool> {
+    return render::points(size.1, size.0, positions.iter().map(|&(x, y)| (y, x)));
+}
+
+// Animate the robots moving step by step, if rendering
+fn render_steps(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (usize, usize), num_steps: usize) -> Result<()> {
+    if !render::enabled() {
+        return Ok(());
+    }
+    let mut animation = Animation::new("robots", num_steps);
+    for step in 0..num_steps {
+        animation.frame(|| room(&positions_after(positions, velocities, size, step), size), render::bool_colors);
+    }
+    return animation.finish(&room(&positions_after(positions, velocities, size, num_steps), size), render::bool_colors);
+}
+
+fn part1(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (usize, usize), num_steps: usize) -> Result<usize> {
+    render_steps(positions, velocities, size, num_steps)?;
+    let traj = positions_after(positions, velocities, size, num_steps);
+    // Get positions of all robots after num_steps steps
     // Count how many are in each quadrant:
     let (mut ne, mut nw, mut se, mut sw) = (0, 0, 0, 0);
     for pos in &traj {
@@ -41,25 +86,18 @@ fn part1(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (u
         se += (pos.0 > size.0/2 && pos.1 > size.1/2) as usize;
         sw += (pos.0 < size.0/2 && pos.1 > size.1/2) as usize;
     }
-    println!("NE: {}, NW: {}, SE: {}, SW: {}", ne, nw, se, sw);
-    return ne*nw*se*sw;
-}
-
-fn create_image(positions: &Vec<(usize, usize)>, size: (usize, usize)) {
-    let mut img = RgbImage::new(size.0 as u32, size.1 as u32);
-    for (x, y) in positions {
-        img.put_pixel(*x as u32, *y as u32, Rgb([255, 255, 255]));
-    }
-    img.save("data/day14.png").unwrap();
+    log::debug!("NE: {}, NW: {}, SE: {}, SW: {}", ne, nw, se, sw);
+    return Ok(ne*nw*se*sw);
 }
 
-fn part2(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (usize, usize)) -> usize {
+fn part2(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (usize, usize)) -> Result<usize> {
     let mut positions = positions.clone();
     // Iterate until all positions are unique
     let mut step = 1;
     let max_unique = 0;
     let mut num_unique = 0;
     loop {
+        budget::check()?;
         let mut pos_set = HashSet::new();
         for (p, v) in positions.iter_mut().zip(velocities.iter()) {
             p.0 = math_mod(p.0 as i64 + v.0, size.0);
@@ -74,14 +112,83 @@ fn part2(positions: &Vec<(usize, usize)>, velocities: &Vec<(i64, i64)>, size: (u
         }
         step += 1;
     }
-    create_image(&positions, size);
-    return step;
+    render::png("tree", &room(&positions, size), render::bool_colors)?;
+    return Ok(step);
 }
 
-pub fn fcn(contents: &String) {
-    let (positions, velocities) = parse_contents(contents);
-    let p1 = part1(&positions, &velocities, (101, 103));
-    println!("Part 1: {}", p1);
-    let p2 = part2(&positions, &velocities, (101, 103));
-    println!("Part 2: {}", p2);
-}
\ No newline at end of file
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = (Params, Vec<(usize, usize)>, Vec<(i64, i64)>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return self.parse_with(contents, &DayParams::default());
+    }
+
+    fn parse_with(&self, contents: &str, params: &DayParams) -> Result<Self::Input> {
+        let params: Params = params.get()?;
+        if params.size.0 == 0 || params.size.1 == 0 {
+            return Err(Error::params(format!("size must be at least 1x1, got {}x{}", params.size.0, params.size.1)));
+        }
+        let (positions, velocities) = parse_contents(contents)?;
+        return Ok((params, positions, velocities));
+    }
+
+    fn part1(&self, (params, positions, velocities): &Self::Input) -> Result<Answer> {
+        return Ok(part1(positions, velocities, params.size, params.steps)?.into());
+    }
+
+    fn part2(&self, (params, positions, velocities): &Self::Input) -> Result<Answer> {
+        return Ok(part2(positions, velocities, params.size)?.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Parts, Runner};
+    use std::time::Duration;
+
+    const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
+    #[test]
+    fn example_part1() {
+        // The example uses a smaller room than the puzzle input
+        let input = Day14.parse_with(EXAMPLE, &DayParams::from_toml("size = [11, 7]").unwrap()).unwrap();
+        assert_eq!(Day14.part1(&input).unwrap().to_string(), "12");
+    }
+
+    #[test]
+    fn part2_times_out() {
+        // Two robots moving together never end up on unique positions, so part 2 searches until its time limit
+        let params = DayParams::from_toml("size = [11, 7]").unwrap();
+        let result = Day14.run("p=0,0 v=1,1\np=0,0 v=1,1", &params, Parts::Part2, Some(Duration::from_millis(20))).unwrap();
+        assert!(result.part1.is_none());
+        assert!(matches!(result.part2.unwrap().answer, Err(Error::Timeout(limit)) if limit == Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn empty_room() {
+        let err = Day14.parse_with(EXAMPLE, &DayParams::from_toml("size = [0, 7]").unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid parameters: size must be at least 1x1, got 0x7");
+    }
+
+    #[test]
+    fn negative_modulus() {
+        assert_eq!(math_mod(-1, 5), 4);
+        assert_eq!(math_mod(-11, 5), 4);
+        assert_eq!(math_mod(7, 5), 2);
+    }
+}
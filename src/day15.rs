@@ -1,165 +1,104 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Direction {
-    North,
-    South,
-    East,
-    West
-}
+#![allow(clippy::type_complexity)]
+
+use crate::error::{invalid_char, Error, Result};
+use crate::geometry::Direction;
+use crate::grid::Grid;
+use crate::render::{self, Animation};
+use crate::solution::{Answer, Solution};
 
-fn parse_contents(contents: &String, double_block: bool) -> ((usize,usize),Vec<Vec<char>>,Vec<Direction>) {
+fn parse_contents(contents: &str, double_block: bool) -> Result<((usize,usize),Grid<char>,Vec<Direction>)> {
     // Parse the contents of the file into a position, grid of characters, and a list of directions
-    // Return where the robot (represented by @)
-    let mut pos = (0,0);
-    let mut grid = Vec::new();
-    let mut split_contents = contents.split("\n\n");
-    let grid_str = split_contents.next().unwrap();
-    let directions_str = split_contents.next().unwrap().replace("\n", "");
-    // Parse the grid
-    for (y, line) in grid_str.lines().enumerate() {
-        let mut row = Vec::new();
-        for (x, c) in line.chars().enumerate() {
-            if c == '@' {
-                if double_block {
-                    pos = (2*x,y);
-                } else {
-                    pos = (x,y);
-                }
-            }
-            if double_block {
-                if c == 'O' {
-                    row.push('[');
-                    row.push(']');
-                } else if c == '#' {
-                    row.push('#');
-                    row.push('#');
-                } else if c == '@' {
-                    row.push(c);
-                    row.push('.');
-                } else {
-                    row.push(c);
-                    row.push(c);
-                }
-            } else {
-                row.push(c);
-            }
-        }
-        grid.push(row);
-    }
-    // Parse the directions
-    let directions = directions_str.chars().enumerate().map(|(i, c)| {
-        match c {
-            '^' => Direction::North,
-            'v' => Direction::South,
-            '<' => Direction::East,
-            '>' => Direction::West,
-            _ => panic!("Invalid direction {} at index {}", c, i)
-        }
-    }).collect();
-    return (pos, grid, directions);
+    // Return where the robot (represented by @) is as (row, col)
+    let Some((grid_str, directions_str)) = contents.split_once("\n\n") else {
+        return Err(Error::invalid("Expected the warehouse and the moves separated by an empty line"));
+    };
+    // Parse the grid, where every block is twice as wide when doubled
+    let grid = if double_block {
+        let doubled = grid_str.chars().map(|c| match c {
+            'O' => "[]".to_string(),
+            '@' => "@.".to_string(),
+            '\n' => "\n".to_string(),
+            c => format!("{}{}", c, c),
+        }).collect::<String>();
+        Grid::parse_chars(&doubled)?
+    } else {
+        Grid::parse_chars(grid_str)?
+    };
+    let Some(pos) = grid.find(&'@') else {
+        return Err(Error::invalid("No robot `@` in the warehouse"));
+    };
+    // Parse the directions, which may be split over several lines
+    let directions = directions_str.chars().enumerate().filter(|(_, c)| *c != '\n').map(|(i, c)| {
+        return Direction::from_arrow(c).ok_or_else(|| invalid_char(contents, directions_str, i, c, "direction"));
+    }).collect::<Result<_>>()?;
+    return Ok((pos, grid, directions));
 }
 
-fn attempt_step(grid: &mut Vec<Vec<char>>, pos: (usize,usize), dir: Direction) -> (usize,usize) {
+fn attempt_step(grid: &mut Grid<char>, pos: (usize,usize), dir: Direction) -> (usize,usize) {
     // Attempt to move the robot in the given direction
-    let (x,y) = pos;
-    let (dx,dy) = match dir {
-        Direction::North => (0,-1),
-        Direction::South => (0,1),
-        Direction::East => (-1,0),
-        Direction::West => (1,0)
-    };
-    let new_pos = (x as i32 + dx, y as i32 + dy);
-    if new_pos.0 < 0 || new_pos.1 < 0 {
+    let Some(new_pos) = grid.step(pos, dir.offset()) else {
         return pos;
-    }
-    let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-    if new_pos.1 >= grid.len() || new_pos.0 >= grid[0].len() {
-        return pos;
-    }
-    if grid[new_pos.1][new_pos.0] == '#' {
+    };
+    if grid[new_pos] == '#' {
         return pos;
     }
-    if grid[new_pos.1][new_pos.0] == 'O' {
+    if grid[new_pos] == 'O' {
         let crate_pos = attempt_step(grid, new_pos, dir);
         if crate_pos == new_pos {
             return pos;
         }
     }
-    grid[new_pos.1][new_pos.0] = grid[y][x];
-    grid[y][x] = '.';
+    grid[new_pos] = grid[pos];
+    grid[pos] = '.';
     return new_pos;
 }
 
-fn print_grid(grid: &Vec<Vec<char>>) {
-    // Print the grid
-    for row in grid {
-        for c in row {
-            print!("{}", c);
-        }
-        println!();
-    }
-}
-
 fn get_gps(i: usize, j: usize) -> usize {
     return i*100 + j;
 }
 
-fn part1(pos: (usize,usize), grid: Vec<Vec<char>>, directions: Vec<Direction>) -> usize{
+// Sum of the GPS coordinates of every box, whose left edge is `c`
+fn gps_sum(grid: &Grid<char>, c: char) -> usize {
+    return grid.iter().filter(|(_, &cell)| cell == c).map(|((i, j), _)| get_gps(i, j)).sum();
+}
+
+fn part1(pos: (usize,usize), grid: Grid<char>, directions: Vec<Direction>) -> Result<usize> {
     // Part 1: Move the robot through the grid
     let mut grid = grid;
     let mut pos = pos;
+    let mut animation = Animation::new("part1-moves", directions.len());
     for dir in directions {
+        animation.frame(|| grid.clone(), render::map_colors);
         pos = attempt_step(&mut grid, pos, dir);
     }
-    print_grid(&grid);
-    let mut gps_sum = 0;
-    for (i, row) in grid.iter().enumerate() {
-        for (j, c) in row.iter().enumerate() {
-            if *c == 'O' {
-                let gps = get_gps(i,j);
-                gps_sum += gps;
-            }
-        }
-    }
-    return gps_sum;
+    log::trace!("Final grid:\n{}", grid);
+    animation.finish(&grid, render::map_colors)?;
+    return Ok(gps_sum(&grid, 'O'));
 }
 
-fn can_double_step(grid: &Vec<Vec<char>>, pos: (usize,usize), dir: Direction) -> bool {
+fn can_double_step(grid: &Grid<char>, pos: (usize,usize), dir: Direction) -> bool {
     // Attempt to move the robot in the given direction
-    let (x,y) = pos;
-    let (dx,dy) = match dir {
-        Direction::North => (0,-1),
-        Direction::South => (0,1),
-        Direction::East => (-1,0),
-        Direction::West => (1,0)
-    };
-    let new_pos = (x as i32 + dx, y as i32 + dy);
-    
-    if new_pos.0 < 0 || new_pos.1 < 0 {
+    let Some(new_pos) = grid.step(pos, dir.offset()) else {
         return false;
-    }
-    let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-    
-    if new_pos.1 >= grid.len() || new_pos.0 >= grid[0].len() {
-        return false;
-    }
+    };
     
-    if grid[new_pos.1][new_pos.0] == '#' {
+    if grid[new_pos] == '#' {
         return false;
     }
     
-    if grid[new_pos.1][new_pos.0] == '[' {
+    if grid[new_pos] == '[' {
         if dir == Direction::North || dir == Direction::South {
             let crate_left = can_double_step(grid, new_pos, dir);
-            let crate_right = can_double_step(grid, (new_pos.0+1, new_pos.1), dir);
+            let crate_right = can_double_step(grid, (new_pos.0, new_pos.1+1), dir);
             return crate_left && crate_right;
         } else {
             return can_double_step(grid, new_pos, dir);
         }
     }
     
-    if grid[new_pos.1][new_pos.0] == ']' {
+    if grid[new_pos] == ']' {
         if dir == Direction::North || dir == Direction::South {
-            let crate_left = can_double_step(grid, (new_pos.0-1, new_pos.1), dir);
+            let crate_left = can_double_step(grid, (new_pos.0, new_pos.1-1), dir);
             let crate_right = can_double_step(grid, new_pos, dir);
             return crate_left && crate_right;
         } else {
@@ -170,74 +109,123 @@ fn can_double_step(grid: &Vec<Vec<char>>, pos: (usize,usize), dir: Direction) ->
     return true;
 }
 
-fn perform_double_step(grid: &mut Vec<Vec<char>>, pos: (usize, usize), dir: Direction) -> (usize, usize) {
-    let c = grid[pos.1][pos.0];
+fn perform_double_step(grid: &mut Grid<char>, pos: (usize, usize), dir: Direction) -> (usize, usize) {
+    let c = grid[pos];
     if c == '.' {
-        // println!("Found empty space at {:?}", pos);
         return pos;
     }
-    let (x,y) = pos;
-    let (dx,dy) = match dir {
-        Direction::North => (0,-1),
-        Direction::South => (0,1),
-        Direction::East => (-1,0),
-        Direction::West => (1,0)
-    };
-    let new_pos = (x as i32 + dx, y as i32 + dy);
-    let new_pos = (new_pos.0 as usize, new_pos.1 as usize);
-    // println!("Moving {:?} to {:?}", pos, new_pos);
+    let (row, col) = pos;
+    // `can_double_step` checked that nothing moves off the grid
+    let new_pos = grid.step(pos, dir.offset()).unwrap();
+    log::trace!("Moving {:?} to {:?}", pos, new_pos);
     perform_double_step(grid, new_pos, dir);
     if dir == Direction::North || dir == Direction::South {
         if c == '[' {
-            let right_new_pos = (new_pos.0+1, new_pos.1);
+            let right_new_pos = (new_pos.0, new_pos.1+1);
             perform_double_step(grid, right_new_pos, dir);
-            grid[right_new_pos.1][right_new_pos.0] = ']';
-            grid[y][x+1] = '.';
+            grid[right_new_pos] = ']';
+            grid[(row, col+1)] = '.';
         }
         if c == ']' {
-            let left_new_pos = (new_pos.0-1, new_pos.1);
+            let left_new_pos = (new_pos.0, new_pos.1-1);
             perform_double_step(grid, left_new_pos, dir);
-            grid[left_new_pos.1][left_new_pos.0] = '[';
-            grid[y][x-1] = '.';
+            grid[left_new_pos] = '[';
+            grid[(row, col-1)] = '.';
         }
     }
-    grid[new_pos.1][new_pos.0] = c;
-    grid[y][x] = '.';
+    grid[new_pos] = c;
+    grid[pos] = '.';
     return new_pos;
 }
 
-fn part2(pos: (usize,usize), grid: Vec<Vec<char>>, directions: Vec<Direction>) -> usize {
+fn part2(pos: (usize,usize), grid: Grid<char>, directions: Vec<Direction>) -> Result<usize> {
     // Part 2: Move the robot through the grid
     let mut grid = grid;
     let mut pos = pos;
-    for (_iter,dir) in directions.iter().enumerate() {
-        // println!("Iteration {}: Moving {:?}", iter, dir);
-        // print_grid(&grid);
+    let mut animation = Animation::new("part2-moves", directions.len());
+    for (iter, dir) in directions.iter().enumerate() {
+        log::trace!("Iteration {}: Moving {:?}\n{}", iter, dir, grid);
+        animation.frame(|| grid.clone(), render::map_colors);
         let can_step = can_double_step(&grid, pos, *dir);
-        // println!("Can step: {}\n", can_step);
+        log::trace!("Can step: {}", can_step);
         if can_step {
-            // println!("Performing double step at {:?}", pos);
             pos = perform_double_step(&mut grid, pos, *dir);
         }
     }
-    // print_grid(&grid);
-    let mut gps_sum = 0;
-    for (i, row) in grid.iter().enumerate() {
-        for (j, c) in row.iter().enumerate() {
-            if *c == '[' {
-                let gps = get_gps(i,j);
-                gps_sum += gps;
-            }
-        }
+    log::trace!("Final grid:\n{}", grid);
+    animation.finish(&grid, render::map_colors)?;
+    return Ok(gps_sum(&grid, '['));
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    // The warehouse as given, and with every block doubled in width
+    type Input = [((usize,usize),Grid<char>,Vec<Direction>); 2];
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return Ok([parse_contents(contents, false)?, parse_contents(contents, true)?]);
+    }
+
+    fn part1(&self, [(pos, grid, directions), _]: &Self::Input) -> Result<Answer> {
+        return Ok(part1(*pos, grid.clone(), directions.clone())?.into());
+    }
+
+    fn part2(&self, [_, (pos, grid, directions)]: &Self::Input) -> Result<Answer> {
+        return Ok(part2(*pos, grid.clone(), directions.clone())?.into());
     }
-    return gps_sum;
 }
 
-pub fn fcn(contents: &String) {
-    let (pos, grid, directions) = parse_contents(contents, false);
-    let p1 = part1(pos, grid, directions);
-    println!("Part 1: {}", p1);
-    let (pos, grid, directions) = parse_contents(contents, true);
-    let p2 = part2(pos, grid, directions);
-    println!("Part 2: {}", p2);
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_SMALL: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<
+";
+
+    const EXAMPLE_LARGE: &str = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day15.parse(EXAMPLE_SMALL).unwrap();
+        assert_eq!(Day15.part1(&input).unwrap().to_string(), "2028");
+        let input = Day15.parse(EXAMPLE_LARGE).unwrap();
+        assert_eq!(Day15.part1(&input).unwrap().to_string(), "10092");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day15.parse(EXAMPLE_LARGE).unwrap();
+        assert_eq!(Day15.part2(&input).unwrap().to_string(), "9021");
+    }
+}
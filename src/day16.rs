@@ -1,153 +1,175 @@
+use crate::error::{Error, Result};
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::render;
+use crate::search;
+use crate::solution::{Answer, Solution};
+use std::collections::HashSet;
+
 const TURN_COST: usize = 1000;
 const STRAIGHT_COST: usize = 1;
 
-#[derive(Copy,Clone,PartialEq,Eq,Debug)]
-enum Direction {
-    RowUp,
-    RowDn,
-    ColLf,
-    ColRt
-}
-
-const START_DIR: Direction = Direction::ColRt;
+const START_DIR: Direction = Direction::East;
 
-struct Maze {
-    maze: Vec<Vec<bool>>, // Shows where each wall is
+pub struct Maze {
+    maze: Grid<bool>, // Shows where each wall is
     start: (usize, usize),
     end: (usize, usize)
 }
 
 // Parse contents as maze
-fn parse_contents(contents: &String) -> Maze {
-    let mut maze: Vec<Vec<bool>> = Vec::new();
-    let mut start = (0,0);
-    let mut end = (0,0);
-    for (row,line) in contents.lines().enumerate() {
-        let mut maze_row = Vec::new();
-        for (col, c) in line.chars().enumerate() {
-            match c {
-                'S' => {start = (row, col);},
-                'E' => {end = (row, col);},
-                _ => {}
-            }
-            maze_row.push(c == '#'); // Push whether it is wall
-        }
-        maze.push(maze_row);
-    }
-    return Maze{maze, start, end};
+fn parse_contents(contents: &str) -> Result<Maze> {
+    let map = Grid::parse_chars(contents)?;
+    let (Some(start), Some(end)) = (map.find(&'S'), map.find(&'E')) else {
+        return Err(Error::invalid("The maze needs a start `S` and an end `E`"));
+    };
+    return Ok(Maze{maze: map.map(|&c| c == '#'), start, end});
 }
 
-fn get_offset(dir: Direction) -> (i8, i8) {
-    return match dir {
-        Direction::ColLf => {(0,-1)},
-        Direction::RowUp => {(-1,0)},
-        Direction::RowDn => {( 1,0)},
-        Direction::ColRt => {( 0,1)},
-    }
+// Position after a step in `dir`, if it is in the maze and not a wall
+fn valid_step(maze: &Maze, curr_pos: (usize, usize), dir: Direction) -> Option<(usize, usize)> {
+    return maze.maze.step(curr_pos, dir.offset()).filter(|&next_pos| !maze.maze[next_pos]);
 }
 
-fn get_next_pos(curr_pos: (usize, usize), offset: (i8, i8)) -> Option<(usize, usize)> {
-    if offset.0 < 0 && curr_pos.0 == 0 {
-        return None;
-    }
-    if offset.1 < 0 && curr_pos.1 == 0 {
-        return None;
-    }
-    let row = if offset.0 < 0 {curr_pos.0 - 1} else {curr_pos.0 + offset.0 as usize};
-    let col = if offset.1 < 0 {curr_pos.1 - 1} else {curr_pos.1 + offset.1 as usize};
-    return Some((row, col));
+// Tile the reindeer is on and the direction it faces
+type State = ((usize, usize), Direction);
+
+// States one move away, with the cost of turning and moving there
+fn next_states(maze: &Maze, (curr_pos, curr_dir): State) -> impl Iterator<Item = (State, usize)> + '_ {
+    return Direction::ALL.into_iter().filter_map(move |next_dir| {
+        let next_pos = valid_step(maze, curr_pos, next_dir)?;
+        let next_cost = if next_dir == curr_dir {STRAIGHT_COST} else if next_dir == curr_dir.opposite() {STRAIGHT_COST+2*TURN_COST} else {STRAIGHT_COST+TURN_COST};
+        return Some(((next_pos, next_dir), next_cost));
+    });
 }
 
-fn is_valid_step(maze: &Maze, curr_pos: (usize, usize), dir: Direction) -> ((usize, usize), bool) {
-    let offset = get_offset(dir);
-    let next_pos_opt = get_next_pos(curr_pos, offset);
-    if next_pos_opt.is_none() {
-        return ((0,0), false);
-    }
-    let next_pos = next_pos_opt.unwrap();
-    if next_pos.0 >= maze.maze.len() || next_pos.1 >= maze.maze[next_pos.0].len() {
-        return ((0,0), false);
-    }
-    let is_wall = maze.maze[next_pos.0][next_pos.1];
-    if is_wall {
-        return ((0,0), false);
+// Find quickest way through maze
+fn solve_maze(maze: &Maze) -> Option<(usize,Vec<(usize,usize)>)> {
+    // Turning costs more than moving, so search over (position, direction) states.
+    // Every move costs at least 1, so the distance left to the end never overestimates the cost
+    let end = Point::from(maze.end);
+    let found = search::astar(
+        (maze.start, START_DIR),
+        |&state| next_states(maze, state),
+        |&(pos, _)| Point::from(pos).manhattan(end),
+        |&(pos, _)| pos == maze.end,
+    );
+    let history = found.goal_path()?.into_iter().map(|(pos, _)| pos).collect();
+    return Some((found.goal_cost()?, history));
+}
+
+// Every tile on any of the cheapest paths through the maze
+fn best_tiles(maze: &Maze) -> Option<Vec<(usize,usize)>> {
+    // The end may be reached facing more than one way for the same cost. Those ways in all cost the same as the
+    // first one found, so the search has already seen every cheapest path into them.
+    let found = search::dijkstra((maze.start, START_DIR), |&state| next_states(maze, state), |&(pos, _)| pos == maze.end);
+    let best = found.goal_cost()?;
+    let ends = Direction::ALL.map(|dir| (maze.end, dir));
+    let best_ends = ends.iter().filter(|end| found.cost(end) == Some(best));
+    let tiles: HashSet<(usize,usize)> = found.on_cheapest_paths(best_ends).into_iter().map(|(pos, _)| pos).collect();
+    return Some(tiles.into_iter().collect());
+}
+
+// Draw the maze with the tiles of `history` marked, for tracing and rendering
+fn history_drawing(maze: &Maze, history: &Vec<(usize,usize)>) -> Grid<char> {
+    let mut maze_chars = maze.maze.map(|&wall| if wall {'#'} else {'.'});
+    for &pos in history {
+        maze_chars[pos] = 'X';
     }
-    return (next_pos, true);
+    return maze_chars;
 }
 
-fn opp_direction(dir: Direction) -> Direction {
-    return match dir {
-        Direction::ColLf => {Direction::ColRt},
-        Direction::ColRt => {Direction::ColLf},
-        Direction::RowDn => {Direction::RowUp},
-        Direction::RowUp => {Direction::RowDn},
+fn part1(maze: &Maze) -> Result<usize> {
+    let Some((maze_cost, maze_history)) = solve_maze(maze) else {
+        return Err(Error::invalid("There is no path from the start to the end of the maze"));
     };
+    let drawing = history_drawing(maze, &maze_history);
+    log::trace!("Cheapest path:\n{}", drawing);
+    render::png("path", &drawing, render::map_colors)?;
+    return Ok(maze_cost);
 }
 
-// Find quickest way through maze
-fn solve_maze(maze: &Maze) -> (usize,Vec<(usize,usize)>) {
-    // Treat the graph as (position, direction, cost) tuple
-    let mut next_visit: Vec<((usize,usize),Direction,usize,Vec<(usize,usize)>)> = Vec::new();
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; maze.maze[0].len()]; maze.maze.len()];
-    next_visit.push((maze.start, START_DIR, 0, Vec::new()));
-    let mut min_cost = usize::MAX;
-    let mut min_cost_history: Option<Vec<(usize,usize)>> = None;
-    loop {
-        let curr_pos_opt = next_visit.pop();
-        if curr_pos_opt.is_none() {
-            break;
-        }
-        let (curr_pos, curr_dir, curr_cost, history) = curr_pos_opt.unwrap();
-        if curr_pos == maze.end {
-            if curr_cost < min_cost {
-                min_cost = curr_cost;
-                min_cost_history = Some(history);
-            }
-            continue;
-        }
-        if visited[curr_pos.0][curr_pos.1] {
-            continue;
-        }
-        visited[curr_pos.0][curr_pos.1] = true;
-        let mut history_copy = history.clone();
-        history_copy.push(curr_pos);
-        for next_dir in vec![Direction::ColLf, Direction::ColRt, Direction::RowUp, Direction::RowDn] {
-            let (next_pos, is_valid) = is_valid_step(maze, curr_pos, next_dir);
-            if !is_valid {
-                continue;
-            }
-            let next_cost = if next_dir == curr_dir {STRAIGHT_COST} else if next_dir == opp_direction(curr_dir) {STRAIGHT_COST+2*TURN_COST} else {STRAIGHT_COST+TURN_COST};
-            next_visit.push((next_pos, next_dir, curr_cost + next_cost, history_copy.clone()));
-        }
-    }
-    return (min_cost, min_cost_history.unwrap());
+fn part2(maze: &Maze) -> Result<usize> {
+    let Some(tiles) = best_tiles(maze) else {
+        return Err(Error::invalid("There is no path from the start to the end of the maze"));
+    };
+    let drawing = history_drawing(maze, &tiles);
+    log::trace!("Tiles on a cheapest path:\n{}", drawing);
+    render::png("best-tiles", &drawing, render::map_colors)?;
+    return Ok(tiles.len());
 }
 
-fn print_maze_history(maze: &Maze, history: &Vec<(usize,usize)>) {
-    let mut maze_chars: Vec<Vec<char>> = Vec::new();
-    for row in 0..maze.maze.len() {
-        let mut maze_row = Vec::new();
-        for col in 0..maze.maze[row].len() {
-            maze_row.push(if maze.maze[row][col] {'#'} else {'.'});
-        }
-        maze_chars.push(maze_row);
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Maze;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
     }
-    for pos in history {
-        maze_chars[pos.0][pos.1] = 'X';
+
+    fn part1(&self, maze: &Self::Input) -> Result<Answer> {
+        return Ok(part1(maze)?.into());
     }
-    for row in maze_chars {
-        println!("{}", row.iter().collect::<String>());
+
+    fn part2(&self, maze: &Self::Input) -> Result<Answer> {
+        return Ok(part2(maze)?.into());
     }
 }
 
-fn part1(maze: &Maze) -> usize {
-    let (maze_cost, maze_history) = solve_maze(maze);
-    print_maze_history(maze, &maze_history);
-    return maze_cost;
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_SMALL: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
 
-pub fn fcn(contents: &String) {
-    let maze = parse_contents(contents);
-    let p1 = part1(&maze);
-    println!("Part 1: {p1}");
-}
\ No newline at end of file
+    const EXAMPLE_LARGE: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day16.parse(EXAMPLE_SMALL).unwrap();
+        assert_eq!(Day16.part1(&input).unwrap().to_string(), "7036");
+        let input = Day16.parse(EXAMPLE_LARGE).unwrap();
+        assert_eq!(Day16.part1(&input).unwrap().to_string(), "11048");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day16.parse(EXAMPLE_SMALL).unwrap();
+        assert_eq!(Day16.part2(&input).unwrap().to_string(), "45");
+        let input = Day16.parse(EXAMPLE_LARGE).unwrap();
+        assert_eq!(Day16.part2(&input).unwrap().to_string(), "64");
+    }
+}
@@ -1,8 +1,15 @@
+#![allow(clippy::ptr_arg)]
+
+use crate::budget;
+use crate::error::{parse_at, Error, Result};
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 use itertools::Itertools;
 
+/// Instructions of the 3-bit computer. `BUF` pads the start of a program so that instruction
+/// pointers line up with the jump targets.
 #[derive(Copy, Clone, Debug)]
-enum Opcode {
+pub enum Opcode {
     ADV,
     BXL,
     BST,
@@ -14,61 +21,76 @@ enum Opcode {
     BUF
 }
 
-fn to_opcode(s: u8) -> Opcode {
+fn to_opcode(s: u8) -> Option<Opcode> {
     return match s {
-        0 => Opcode::ADV,
-        1 => Opcode::BXL,
-        2 => Opcode::BST,
-        3 => Opcode::JNZ,
-        4 => Opcode::BXC,
-        5 => Opcode::OUT,
-        6 => Opcode::BDV,
-        7 => Opcode::CDV,
-        _ => panic!("Invalid opcode")
+        0 => Some(Opcode::ADV),
+        1 => Some(Opcode::BXL),
+        2 => Some(Opcode::BST),
+        3 => Some(Opcode::JNZ),
+        4 => Some(Opcode::BXC),
+        5 => Some(Opcode::OUT),
+        6 => Some(Opcode::BDV),
+        7 => Some(Opcode::CDV),
+        _ => None
     }
 }
 
+// Whether the opcode interprets its operand as a combo operand
+fn takes_combo(opcode: Opcode) -> bool {
+    return matches!(opcode, Opcode::ADV | Opcode::BST | Opcode::OUT | Opcode::BDV | Opcode::CDV);
+}
+
+/// State of the 3-bit computer: the program as (opcode, operand) pairs, what it has output so far,
+/// the instruction pointer and the registers A, B and C
 #[derive(Clone)]
-struct ProgramState {
-    program: Vec<(Opcode, u8)>,
-    output: Vec<u8>,
-    ptr: usize,
-    reg: [u64; 3]
+pub struct ProgramState {
+    pub program: Vec<(Opcode, u8)>,
+    pub output: Vec<u8>,
+    pub ptr: usize,
+    pub reg: [u64; 3]
 }
 
-fn print_state(state: &ProgramState) {
-    println!("Program: {:?}", state.program);
-    println!("Output: {:?}", state.output);
-    println!("Ptr: {:?}", state.ptr);
-    println!("Reg: {:?}", state.reg);
+fn state_string(state: &ProgramState) -> String {
+    return format!("Program: {:?}\nOutput: {:?}\nPtr: {:?}\nReg: {:?}", state.program, state.output, state.ptr, state.reg);
 }
 
-fn read_contents(contents: &String) -> (ProgramState, Vec<u8>) {
+/// Parse the registers and program, returning the initial state and the program as raw numbers
+pub fn read_contents(contents: &str) -> Result<(ProgramState, Vec<u8>)> {
     let mut regs = [0u64; 3];
     let mut program: Vec<(Opcode, u8)> = Vec::new();
     let mut program_u8: Vec<u8> = Vec::new();
+    // Match the program 1a,1b,2a,2b,3a,3b,... as [(1a,1b),(2a,2b),(3a,3b),...]
+    let re = Regex::new(r"(\d),(\d)").unwrap();
     for (i,line) in contents.lines().enumerate() {
         if i < 3 {
-            regs[i] = line.split(" ").last().unwrap().parse::<u64>().unwrap();
+            regs[i] = parse_at(contents, line.rsplit(" ").next().unwrap())?;
         }
         if i == 4 {
-            program_u8 = line.split(" ").last().unwrap()
-                .split(",").map(|x| x.parse().unwrap()).collect();
-            // Match the program 1a,1b,2a,2b,3a,3b,... as [(1a,1b),(2a,2b),(3a,3b),...]
-            let re = Regex::new(r"(\d),(\d)").unwrap();
-            program = re.captures_iter(line).map(|c|
-                return (to_opcode(c[1].parse().unwrap()), c[2].parse().unwrap())
-            ).collect();
+            program_u8 = line.rsplit(" ").next().unwrap().split(",").map(|x| parse_at(contents, x)).collect::<Result<_>>()?;
+            program = re.captures_iter(line).map(|c| {
+                let (opcode, operand) = (c.get(1).unwrap(), c.get(2).unwrap());
+                let Some(op) = to_opcode(parse_at(contents, opcode.as_str())?) else {
+                    return Err(Error::parse_at(contents, opcode.as_str(), format!("Invalid opcode `{}`", opcode.as_str())));
+                };
+                let arg: u8 = parse_at(contents, operand.as_str())?;
+                if takes_combo(op) && arg == 7 {
+                    return Err(Error::parse_at(contents, operand.as_str(), "Combo operand 7 is reserved"));
+                }
+                return Ok((op, arg));
+            }).collect::<Result<_>>()?;
         }
     }
+    if program.is_empty() {
+        return Err(Error::invalid("The program is empty"));
+    }
     program.insert(0,(Opcode::BUF,0));
     let state = ProgramState {
-        program: program,
+        program,
         output: Vec::new(),
         ptr: 1,
         reg: regs
     };
-    return (state, program_u8);
+    return Ok((state, program_u8));
 }
 
 #[inline]
@@ -102,7 +124,7 @@ fn instruction_adv(state: &mut ProgramState, arg: u8) {
 #[inline]
 fn instruction_bxl(state: &mut ProgramState, arg: u8) {
     // XOR: B ^ arg (literal) -> B
-    state.reg[1] = state.reg[1] ^ (arg as u64);
+    state.reg[1] ^= arg as u64;
 }
 
 #[inline]
@@ -122,7 +144,7 @@ fn instruction_jnz(state: &mut ProgramState, arg: u8) {
 #[inline]
 fn instruction_bxc(state: &mut ProgramState, _: u8) {
     // BXC: B ^ C -> B
-    state.reg[1] = state.reg[1] ^ state.reg[2];
+    state.reg[1] ^= state.reg[2];
 }
 
 #[inline]
@@ -144,8 +166,9 @@ fn instruction_cdv(state: &mut ProgramState, arg: u8) {
     state.reg[2] = instruction_dv(state, arg);
 }
 
+/// Run the instruction at the pointer, returning whether it wrote to the output
 #[inline]
-fn instruction(state: &mut ProgramState) -> bool {
+pub fn instruction(state: &mut ProgramState) -> bool {
     let (opcode, arg) = state.program[state.ptr];
     let mut out_change = false;
     match opcode {
@@ -163,7 +186,8 @@ fn instruction(state: &mut ProgramState) -> bool {
     return out_change;
 }
 
-fn execute(state: &mut ProgramState) {
+/// Run the program until it halts
+pub fn execute(state: &mut ProgramState) {
     while state.ptr < state.program.len() {
         instruction(state);
     }
@@ -189,10 +213,8 @@ fn check_output(slice: &Vec<u8>, full_vec: &Vec<u8>) -> bool {
 fn execute_match(state: &mut ProgramState, program: &Vec<u8>) {
     while state.ptr < state.program.len() {
         let out_change = instruction(state);
-        if out_change {
-            if !check_output(&state.output, program) {
-                return;
-            }
+        if out_change && !check_output(&state.output, program) {
+            return;
         }
     }
 }
@@ -204,12 +226,13 @@ fn check_output_is_input(state: &ProgramState, reg_a: u64, program: &Vec<u8>) ->
     return state_c.output == *program;
 }
 
-fn part2_first(state: &ProgramState, program: &Vec<u8>) -> u64 {
+fn part2_first(state: &ProgramState, program: &Vec<u8>) -> Result<u64> {
     let mut reg_a = 0;
     while !check_output_is_input(state, reg_a, program) {
+        budget::check()?;
         reg_a += 1;
     }
-    return reg_a;
+    return Ok(reg_a);
 }
 
 fn hardcoded_program(a_init: u64, predicted_output: &Vec<u8>) -> bool {
@@ -220,31 +243,139 @@ fn hardcoded_program(a_init: u64, predicted_output: &Vec<u8>) -> bool {
         let b2 = (a % 8) ^ 2; // We take bottom three bits of A and negate second bit
         let c1 = a >> b2; // C = A >> B
         let b3 = b2 ^ c1; // B = B ^ C
+        if output_pointer == predicted_output.len() {
+            return false;
+        }
         if predicted_output[output_pointer] == ((b3 % 8) as u8) {
             output_pointer += 1;
         } else {
             return false;
         }
-        a = a >> 3;
+        a >>= 3;
     }
     return output_pointer == predicted_output.len();
 }
 
-fn part2_hardcoded(program: &Vec<u8>) -> u64 {
-    let mut a = 0;
-    while !hardcoded_program(a, program) {
-        a += 1;
+fn part2_hardcoded(program: &Vec<u8>) -> Result<u64> {
+    // The program outputs one digit for each octal digit of A, so only those with as many digits can work
+    let digits = program.len() as u32;
+    let lo = if digits == 0 { 0 } else { 8u64.pow(digits - 1) };
+    let hi = 8u64.checked_pow(digits).unwrap_or(u64::MAX);
+    for a in lo..hi {
+        if hardcoded_program(a, program) {
+            return Ok(a);
+        }
+        budget::check()?;
+    }
+    return Err(Error::invalid("No value of register A makes the hardcoded program output itself"));
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = (ProgramState, Vec<u8>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return read_contents(contents);
+    }
+
+    fn part1(&self, (state, _): &Self::Input) -> Result<Answer> {
+        return Ok(part1(&mut state.clone()).into());
+    }
+
+    fn part2(&self, (state, program_u8): &Self::Input) -> Result<Answer> {
+        log::debug!("program_u8: {:?}", program_u8);
+        log::debug!("{}", state_string(state));
+        return Ok(part2_hardcoded(program_u8)?.into());
+    }
+}
+
+// First attempt at part 2, running the program for every value of register A until it outputs itself.
+// Unlike `part2_hardcoded` it works for any program, but takes far too long for real inputs.
+pub struct Day17Search;
+
+impl Solution for Day17Search {
+    type Input = (ProgramState, Vec<u8>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return read_contents(contents);
+    }
+
+    // Only part 2 has another implementation
+    fn part1(&self, _: &Self::Input) -> Result<Answer> {
+        return Ok(Answer::Unsolved);
+    }
+
+    fn part2(&self, (state, program_u8): &Self::Input) -> Result<Answer> {
+        return Ok(part2_first(state, program_u8)?.into());
     }
-    return a;
 }
 
-pub fn fcn(contents: &String) {
-    let (state, program_u8) = read_contents(contents);
-    let p1 = part1(&mut state.clone());
-    println!("Part 1: {}", p1);
-    println!("program_u8: {:?}", program_u8);
-    print_state(&state);
-    let p2 = part2_hardcoded(&program_u8);
-    println!("Part 2: {}", p2);
-    part2_first(&state, &program_u8);
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_PART1: &str = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+
+    const EXAMPLE_PART2: &str = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0
+";
+
+    // Run a program on the given registers until it halts
+    fn run_program(reg: [u64; 3], program: &str) -> ProgramState {
+        let contents = format!("Register A: {}\nRegister B: {}\nRegister C: {}\n\nProgram: {}", reg[0], reg[1], reg[2], program);
+        let (mut state, _) = read_contents(&contents).unwrap();
+        execute(&mut state);
+        return state;
+    }
+
+    #[test]
+    fn example_part1() {
+        let input = Day17.parse(EXAMPLE_PART1).unwrap();
+        assert_eq!(Day17.part1(&input).unwrap().to_string(), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day17.parse(EXAMPLE_PART2).unwrap();
+        assert_eq!(Day17Search.part2(&input).unwrap(), Answer::UInt(117440));
+        // `Day17` only simulates the program from the real input, which never outputs this one
+        assert!(matches!(Day17.part2(&input), Err(Error::InvalidPuzzle(_))));
+    }
+
+    #[test]
+    fn single_instructions() {
+        let (mut state, _) = read_contents("Register A: 0\nRegister B: 0\nRegister C: 9\n\nProgram: 2,6").unwrap();
+        assert!(!instruction(&mut state));
+        assert_eq!(state.reg[1], 1);
+        assert_eq!(state.ptr, 2);
+
+        let (mut state, _) = read_contents("Register A: 0\nRegister B: 29\nRegister C: 0\n\nProgram: 1,7").unwrap();
+        instruction(&mut state);
+        assert_eq!(state.reg[1], 26);
+
+        let (mut state, _) = read_contents("Register A: 0\nRegister B: 2024\nRegister C: 43690\n\nProgram: 4,0").unwrap();
+        instruction(&mut state);
+        assert_eq!(state.reg[1], 44354);
+
+        let (mut state, _) = read_contents("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 5,3").unwrap();
+        assert!(instruction(&mut state));
+        assert_eq!(state.output, vec![3]);
+    }
+
+    #[test]
+    fn small_programs() {
+        assert_eq!(run_program([10, 0, 0], "5,0,5,1,5,4").output, vec![0, 1, 2]);
+        let state = run_program([2024, 0, 0], "0,1,5,4,3,0");
+        assert_eq!(state.output, vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(state.reg[0], 0);
+    }
+}
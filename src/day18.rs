@@ -1,169 +1,236 @@
+#![allow(clippy::ptr_arg)]
 
-fn find_min_dist(queue: &Vec<usize>, dist: &Vec<usize>) -> usize {
-    let mut min_q_idx = queue.len() - 1;
-    let mut min = dist[queue[min_q_idx]];
-    for (node_idx, &node) in queue.iter().enumerate() {
-        if dist[node] < min {
-            min = dist[node];
-            min_q_idx = node_idx;
-        }
-    }
-    return min_q_idx;
+use crate::error::{parse_at, Error, Result};
+use crate::grid::Grid;
+use crate::params::DayParams;
+use crate::render;
+use crate::search;
+use crate::solution::{Answer, Solution};
+use serde::Deserialize;
+
+/// Size of the memory space and how many bytes have fallen in part 1, which are smaller for the example
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub size: (usize, usize),
+    pub part1_bytes: usize,
 }
 
-fn dijkstra(graph: Vec<Vec<usize>>, start: usize, end: usize) -> usize {
-    // Graph: CSR representation of adjacency matrix. All weights are 1
-    let mut dist = vec![usize::MAX; graph.len()];
-    let mut prev = vec![usize::MAX; graph.len()];
-    dist[start] = 0;
-    let mut queue = (0..graph.len()).collect::<Vec<usize>>();
-    while !queue.is_empty() && dist[end] == usize::MAX {
-        let min_q_idx = find_min_dist(&queue, &dist);
-        let u = queue.remove(min_q_idx);
-        if dist[u] == usize::MAX {
-            break;
-        }
-        for &v in graph[u].iter() {
-            let alt = dist[u] + 1;
-            if alt < dist[v] {
-                dist[v] = alt;
-                prev[v] = u;
-            }
-        }
+impl Default for Params {
+    fn default() -> Self {
+        return Params { size: (71, 71), part1_bytes: 1024 };
     }
-    return dist[end];
 }
 
-fn linear_index(idx: (usize, usize), width: usize) -> usize {
-    return idx.0 * width + idx.1;
+/// Length of the shortest path from `start` to `end`, or `usize::MAX` if there is none
+pub fn shortest_path(graph: Vec<Vec<usize>>, start: usize, end: usize) -> usize {
+    // Graph: CSR representation of adjacency matrix. All weights are 1, so a breadth-first search will do
+    let found = search::bfs(start, |&u| graph[u].iter().copied(), |&u| u == end);
+    return found.goal_cost().unwrap_or(usize::MAX);
 }
 
-fn get_neighbors(idx: (usize, usize), size: (usize, usize)) -> Vec<(usize, usize)> {
-    let mut neighbors = Vec::new();
-    if idx.0 > 0 {
-        neighbors.push((idx.0 - 1, idx.1));
-    }
-    if idx.0 < size.0 - 1 {
-        neighbors.push((idx.0 + 1, idx.1));
-    }
-    if idx.1 > 0 {
-        neighbors.push((idx.0, idx.1 - 1));
-    }
-    if idx.1 < size.1 - 1 {
-        neighbors.push((idx.0, idx.1 + 1));
-    }
-    return neighbors;
+pub fn linear_index(idx: (usize, usize), width: usize) -> usize {
+    return idx.0 * width + idx.1;
 }
 
-fn parse_contents(contents: &String) -> Vec<(usize,usize)> {
+fn parse_contents(contents: &str) -> Result<Vec<(usize,usize)>> {
     // Parse contents as (i,j) coordinates
     let mut coords = Vec::new();
     for line in contents.lines() {
-        let mut coord = line.split(",");
-        let i = coord.next().unwrap().parse::<usize>().unwrap();
-        let j = coord.next().unwrap().parse::<usize>().unwrap();
-        coords.push((i,j));
+        let Some((i, j)) = line.split_once(",") else {
+            return Err(Error::parse_at(contents, line, "Expected a coordinate `x,y`"));
+        };
+        coords.push((parse_at(contents, i)?, parse_at(contents, j)?));
     }
-    return coords;
+    return Ok(coords);
 }
 
-fn create_graph(obstacles: &[(usize,usize)], size: (usize, usize)) -> Vec<Vec<usize>> {
+/// Adjacency lists of the free cells of a `size` grid, indexed by `linear_index`.
+/// Cells are at (x, y), which is the (row, col) of the grid.
+pub fn create_graph(obstacles: &[(usize,usize)], size: (usize, usize)) -> Vec<Vec<usize>> {
+    let mut walls = Grid::new(size.0, size.1, false);
+    for &obstacle in obstacles {
+        if let Some(wall) = walls.get_mut(obstacle) {
+            *wall = true;
+        }
+    }
     let mut graph = vec![vec![]; size.0 * size.1];
-    for i in 0..size.0 {
-        for j in 0..size.1 {
-            let idx = (j,i);
-            if obstacles.contains(&idx) {
-                continue;
-            }
-            let u = linear_index(idx, size.1);
-            let neighbors = get_neighbors(idx, size);
-            for neighbor in neighbors {
-                if !obstacles.contains(&neighbor) {
-                    let v = linear_index(neighbor, size.1);
-                    graph[u].push(v);
-                }
+    for idx in walls.positions() {
+        if walls[idx] {
+            continue;
+        }
+        let u = linear_index(idx, size.1);
+        for neighbor in walls.neighbors4(idx) {
+            if !walls[neighbor] {
+                let v = linear_index(neighbor, size.1);
+                graph[u].push(v);
             }
         }
     }
     return graph;
 }
 
-#[allow(dead_code)]
-fn print_maze(graph: &Vec<Vec<usize>>, size: (usize, usize)) {
-    let width = size.1;
-    let mut chars = vec![vec!['.'; size.1]; size.0];
-    for i in 0..size.0 {
-        for j in 0..size.1 {
-            let idx = (j,i);
-            let u = linear_index(idx, width);
-            if graph[u].len() == 0 {
-                chars[i][j] = '#';
-            }
-        }
-    }
-    for i in 0..size.0 {
-        for j in 0..size.1 {
-            print!("{}", chars[i][j]);
-        }
-        println!();
+// Draw the memory space with y going down the rows, where cells without any free neighbours are walls
+fn maze_drawing(graph: &Vec<Vec<usize>>, size: (usize, usize)) -> Grid<char> {
+    return Grid::from_fn(size.1, size.0, |(y, x)| if graph[linear_index((x, y), size.1)].is_empty() {'#'} else {'.'});
+}
+
+// Render the memory space with a shortest path through it, if there is one
+fn render_path(graph: &Vec<Vec<usize>>, size: (usize, usize), start: usize, end: usize) -> Result<()> {
+    let mut drawing = maze_drawing(graph, size);
+    let found = search::bfs(start, |&u| graph[u].iter().copied(), |&u| u == end);
+    for u in found.goal_path().unwrap_or_default() {
+        drawing[(u % size.1, u / size.1)] = 'X';
     }
+    return render::png("path", &drawing, render::map_colors);
 }
 
-#[allow(dead_code)]
-fn print_graph(graph: &Vec<Vec<usize>>, size: (usize, usize)) {
+fn graph_string(graph: &Vec<Vec<usize>>, size: (usize, usize)) -> String {
     let width = size.1;
+    let mut lines = Vec::new();
     for (idx, neighbors) in graph.iter().enumerate() {
         let i = idx / width;
         let j = idx % width;
-        print!("({},{}): ", i, j);
-        for &neighbor in neighbors.iter() {
-            let i_n = neighbor / width;
-            let j_n = neighbor % width;
-            print!("({},{}), ", i_n, j_n);
-        }
-        println!();
+        let neighbors = neighbors.iter().map(|&neighbor| format!("({},{})", neighbor / width, neighbor % width)).collect::<Vec<_>>();
+        lines.push(format!("({},{}): {}", i, j, neighbors.join(", ")));
     }
+    return lines.join("\n");
 }
 
-const SIZE: (usize, usize) = (71,71);
-const N_OBSTACLES_P1: usize = 1024;
-
-fn part1(obstacles: &Vec<(usize,usize)>)->usize {
+fn part1(obstacles: &Vec<(usize,usize)>, params: &Params)->Result<usize> {
+    let Params { size, part1_bytes } = *params;
     let start = (0,0);
-    let end = (SIZE.0-1,SIZE.1-1);
-    let graph = create_graph(&obstacles[..N_OBSTACLES_P1], SIZE);
-    // print_maze(&graph, SIZE);
-    let u_start = linear_index(start, SIZE.1);
-    let u_end = linear_index(end, SIZE.1);
-    return dijkstra(graph, u_start, u_end);
+    let end = (size.0-1,size.1-1);
+    let graph = create_graph(&obstacles[..part1_bytes], size);
+    log::trace!("Maze:\n{}", maze_drawing(&graph, size));
+    log::trace!("Graph:\n{}", graph_string(&graph, size));
+    let u_start = linear_index(start, size.1);
+    let u_end = linear_index(end, size.1);
+    if render::enabled() {
+        render_path(&graph, size, u_start, u_end)?;
+    }
+    return Ok(shortest_path(graph, u_start, u_end));
 }
 
-fn part2(obstacles: &Vec<(usize,usize)>)->(usize,usize) {
+fn part2(obstacles: &Vec<(usize,usize)>, params: &Params)->Result<(usize,usize)> {
+    let Params { size, part1_bytes } = *params;
     let start = (0,0);
-    let end = (SIZE.0-1,SIZE.1-1);
-    let u_start = linear_index(start, SIZE.1);
-    let u_end = linear_index(end, SIZE.1);
+    let end = (size.0-1,size.1-1);
+    let u_start = linear_index(start, size.1);
+    let u_end = linear_index(end, size.1);
 
+    if shortest_path(create_graph(obstacles, size), u_start, u_end) != usize::MAX {
+        return Err(Error::invalid("The exit is still reachable after every byte has fallen"));
+    }
     // Binary search for the first obstacle that makes the path unreachable
-    let mut lo = N_OBSTACLES_P1;
+    let mut lo = part1_bytes;
     let mut hi = obstacles.len();
     while lo < hi {
         let mid = (lo + hi) / 2;
-        let graph = create_graph(&obstacles[..mid], SIZE);
-        let dist = dijkstra(graph, u_start, u_end);
+        let graph = create_graph(&obstacles[..mid], size);
+        let dist = shortest_path(graph, u_start, u_end);
         if dist == usize::MAX {
             hi = mid;
         } else {
             lo = mid + 1;
         }
     }
-    return obstacles[lo-1];
+    return Ok(obstacles[lo-1]);
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = (Params, Vec<(usize,usize)>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return self.parse_with(contents, &DayParams::default());
+    }
+
+    fn parse_with(&self, contents: &str, params: &DayParams) -> Result<Self::Input> {
+        let params: Params = params.get()?;
+        let obstacles = parse_contents(contents)?;
+        if params.size.0 == 0 || params.size.1 == 0 {
+            return Err(Error::params(format!("size must be at least 1x1, got {}x{}", params.size.0, params.size.1)));
+        }
+        if params.part1_bytes == 0 || params.part1_bytes > obstacles.len() {
+            return Err(Error::params(format!("part1_bytes must be between 1 and the {} bytes in the input, got {}", obstacles.len(), params.part1_bytes)));
+        }
+        return Ok((params, obstacles));
+    }
+
+    fn part1(&self, (params, obstacles): &Self::Input) -> Result<Answer> {
+        return Ok(part1(obstacles, params)?.into());
+    }
+
+    fn part2(&self, (params, obstacles): &Self::Input) -> Result<Answer> {
+        let p2 = part2(obstacles, params)?;
+        return Ok(format!("{},{}", p2.0, p2.1).into());
+    }
 }
 
-pub fn fcn(contents: &String) {
-    let obstacles = parse_contents(contents);
-    let p1 = part1(&obstacles);
-    println!("Part 1: {}", p1);
-    let p2 = part2(&obstacles);
-    println!("Part 2: {},{}", p2.0, p2.1);
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0
+";
+
+    #[test]
+    fn example() {
+        // The example uses a 7x7 grid with the first 12 bytes fallen
+        let params = DayParams::from_toml("size = [7, 7]\npart1_bytes = 12").unwrap();
+        let input = Day18.parse_with(EXAMPLE, &params).unwrap();
+        assert_eq!(Day18.part1(&input).unwrap().to_string(), "22");
+        assert_eq!(Day18.part2(&input).unwrap().to_string(), "6,1");
+    }
+
+    #[test]
+    fn invalid_params() {
+        let parse = |params: &str| Day18.parse_with(EXAMPLE, &DayParams::from_toml(params).unwrap());
+        assert!(matches!(parse("size = [0, 0]"), Err(Error::Params(_))));
+        assert!(matches!(parse("size = [7, 0]\npart1_bytes = 12"), Err(Error::Params(_))));
+        assert!(matches!(parse("size = [7, 7]\npart1_bytes = 0"), Err(Error::Params(_))));
+        // The example only has 25 bytes, fewer than the 1024 of the puzzle
+        assert_eq!(
+            parse("size = [7, 7]").unwrap_err().to_string(),
+            "Invalid parameters: part1_bytes must be between 1 and the 25 bytes in the input, got 1024"
+        );
+        assert!(parse("size = [7, 7]\npart1_bytes = 25").is_ok());
+    }
+
+    #[test]
+    fn example_part2_blocking_byte() {
+        // The path exists before byte `6,1` falls and is cut off after it
+        let obstacles = parse_contents(EXAMPLE).unwrap();
+        let size = (7, 7);
+        let blocking = obstacles.iter().position(|&o| o == (6, 1)).unwrap();
+        let (start, end) = (linear_index((0, 0), size.1), linear_index((6, 6), size.1));
+        assert_ne!(shortest_path(create_graph(&obstacles[..blocking], size), start, end), usize::MAX);
+        assert_eq!(shortest_path(create_graph(&obstacles[..blocking + 1], size), start, end), usize::MAX);
+    }
+}
@@ -1,9 +1,13 @@
+#![allow(clippy::type_complexity)]
+
+use crate::error::{invalid_char, Error, Result};
+use crate::solution::{Answer, Solution};
 // Progress bars
 use indicatif::ProgressBar;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Color {
+pub enum Color {
     White,
     Blue,
     Black,
@@ -11,22 +15,23 @@ enum Color {
     Green
 }
 
-fn parse_color(color: char) -> Color {
+fn parse_color(color: char) -> Option<Color> {
     return match color {
-        'w' => Color::White,
-        'u' => Color::Blue,
-        'b' => Color::Black,
-        'r' => Color::Red,
-        'g' => Color::Green,
-        _ => panic!("Invalid color")
+        'w' => Some(Color::White),
+        'u' => Some(Color::Blue),
+        'b' => Some(Color::Black),
+        'r' => Some(Color::Red),
+        'g' => Some(Color::Green),
+        _ => None
     }
 }
 
-fn number_patterns(pattern: &[Color], towels: &Vec<Vec<Color>>, map: &mut HashMap<Vec<Color>,usize>) -> usize {
+/// Number of ways to make `pattern` out of `towels`, caching the counts by the rest of the pattern
+pub fn number_patterns(pattern: &[Color], towels: &Vec<Vec<Color>>, map: &mut HashMap<Vec<Color>,usize>) -> usize {
     if map.contains_key(pattern) {
         return map[pattern];
     }
-    if pattern.len() == 0 {
+    if pattern.is_empty() {
         return 1;
     }
     let mut current_count = 0;
@@ -39,35 +44,39 @@ fn number_patterns(pattern: &[Color], towels: &Vec<Vec<Color>>, map: &mut HashMa
     return current_count;
 }
 
-fn parse_towels(towels_str: &str) -> Vec<Vec<Color>> {
-    let towel_str_fcn = |towel_str: &str| towel_str.chars().map(parse_color).collect();
-    return towels_str.split(", ").map(towel_str_fcn).collect();
+fn parse_towels(contents: &str, towels_str: &str) -> Result<Vec<Vec<Color>>> {
+    return towels_str.split(", ").map(|towel_str| parse_pattern(contents, towel_str)).collect();
 }
 
-fn parse_pattern(pattern_str: &str) -> Vec<Color> {
-    return pattern_str.chars().map(|c| parse_color(c)).collect();
+// Parse `pattern_str`, a slice of `contents`, as a list of colors
+fn parse_pattern(contents: &str, pattern_str: &str) -> Result<Vec<Color>> {
+    return pattern_str.chars().enumerate().map(|(i, c)| {
+        return parse_color(c).ok_or_else(|| invalid_char(contents, pattern_str, i, c, "color"));
+    }).collect();
 }
 
-fn parse_contents(contents: &String) -> (Vec<Vec<Color>>, Vec<Vec<Color>>) {
+fn parse_contents(contents: &str) -> Result<(Vec<Vec<Color>>, Vec<Vec<Color>>)> {
     let mut lines = contents.lines();
-    let towels_str = lines.next().unwrap();
-    let towels = parse_towels(towels_str);
+    let Some(towels_str) = lines.next() else {
+        return Err(Error::invalid("No towels given"));
+    };
+    let towels = parse_towels(contents, towels_str)?;
     lines.next();
     let mut patterns = Vec::new();
     for pattern_str in lines {
-        patterns.push(parse_pattern(pattern_str));
+        patterns.push(parse_pattern(contents, pattern_str)?);
     }
-    return (patterns, towels);
+    return Ok((patterns, towels));
 }
 
 fn both_parts(patterns: &Vec<Vec<Color>>, towels: &Vec<Vec<Color>>) -> (usize, usize) {
     let mut count = 0;
     let mut total_combos = 0;
-    // Track progress
-    let pb = ProgressBar::new(patterns.len() as u64);
+    // Track progress, but only when tracing so that normal runs print just the answers
+    let pb = if log::log_enabled!(log::Level::Trace) { ProgressBar::new(patterns.len() as u64) } else { ProgressBar::hidden() };
     for pattern in patterns {
         let mut map = HashMap::new();
-        let combo_pattern = number_patterns(&pattern, towels, &mut map);
+        let combo_pattern = number_patterns(pattern, towels, &mut map);
         count += (combo_pattern > 0) as usize;
         total_combos += combo_pattern;
         pb.inc(1);
@@ -77,9 +86,57 @@ fn both_parts(patterns: &Vec<Vec<Color>>, towels: &Vec<Vec<Color>>) -> (usize, u
 }
 
 
-pub fn fcn(contents: &String) {
-    let (patterns, towels) = parse_contents(contents);
-    let (result1, result2) = both_parts(&patterns, &towels);
-    println!("Part 1: {}", result1);
-    println!("Part 2: {}", result2);
-}
\ No newline at end of file
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (Vec<Vec<Color>>, Vec<Vec<Color>>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, (patterns, towels): &Self::Input) -> Result<Answer> {
+        let (count, _) = both_parts(patterns, towels);
+        return Ok(count.into());
+    }
+
+    fn part2(&self, (patterns, towels): &Self::Input) -> Result<Answer> {
+        let (_, total_combos) = both_parts(patterns, towels);
+        return Ok(total_combos.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day19.parse(EXAMPLE).unwrap();
+        assert_eq!(Day19.part1(&input).unwrap().to_string(), "6");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day19.parse(EXAMPLE).unwrap();
+        assert_eq!(Day19.part2(&input).unwrap().to_string(), "16");
+    }
+
+    #[test]
+    fn invalid_color() {
+        let err = Day19.parse("r, wr, b\n\nbrwrr\nbggxr\n").unwrap_err();
+        assert_eq!(err.to_string(), "Parse error at line 4, column 4: Invalid color `x`");
+    }
+}
@@ -1,19 +1,23 @@
+#![allow(clippy::ptr_arg)]
+
+use crate::error::{parse_at, Result};
+use crate::solution::{Answer, Solution};
 use rayon::prelude::*;
 
 /// Parse each line of input as a vector of integers
-fn parse_input(contents: &String) -> Vec<Vec<i32>> {
+fn parse_input(contents: &str) -> Result<Vec<Vec<i32>>> {
     // Initialize a vector to store the parsed input
     let mut result = Vec::new();
     // Iterate over the lines of the input
     for line in contents.lines() {
         // Parse the line as a vector of integers
         let row: Vec<i32> = line.split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
+            .map(|x| parse_at(contents, x))
+            .collect::<Result<_>>()?;
         // Store the parsed row in the result vector
         result.push(row);
     }
-    return result;
+    return Ok(result);
 }
 
 /// Check if a given report is safe, skipping the number at skip_idx
@@ -82,11 +86,53 @@ fn part2(reports: &Vec<Vec<i32>>) -> i64 {
     return count_safe(reports, true) as i64;
 }
 
-/// Function to run the day's solutions
-pub fn fcn(contents: &String) {
-    let reports = parse_input(contents);
-    let p1_ans = part1(&reports);
-    println!("Part 1: {}", p1_ans);
-    let p2_ans = part2(&reports);
-    println!("Part 2: {}", p2_ans);
-}
\ No newline at end of file
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_input(contents);
+    }
+
+    fn part1(&self, reports: &Self::Input) -> Result<Answer> {
+        return Ok(part1(reports).into());
+    }
+
+    fn part2(&self, reports: &Self::Input) -> Result<Answer> {
+        return Ok(part2(reports).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day2.parse(EXAMPLE).unwrap();
+        assert_eq!(Day2.part1(&input).unwrap().to_string(), "2");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day2.parse(EXAMPLE).unwrap();
+        assert_eq!(Day2.part2(&input).unwrap().to_string(), "4");
+    }
+
+    #[test]
+    fn safe_with_skipped_level() {
+        assert!(!is_safe(&vec![1, 3, 2, 4, 5], usize::MAX));
+        assert!(is_safe(&vec![1, 3, 2, 4, 5], 1));
+        assert!(is_safe(&vec![1, 3, 2, 4, 5], 2));
+        assert!(is_safe(&vec![9, 1, 2, 3], 0));
+    }
+}
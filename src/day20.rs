@@ -1,4 +1,16 @@
-pub fn fcn(_: &String) {
-    // Get the distance from one number to another
-    println!("Day 20: Not yet implemented");
-}
\ No newline at end of file
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = ();
+
+    fn parse(&self, _: &str) -> Result<Self::Input> {
+        return Ok(());
+    }
+
+    fn part1(&self, _: &Self::Input) -> Result<Answer> {
+        return Ok(Answer::Unsolved);
+    }
+}
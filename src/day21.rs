@@ -1,6 +1,28 @@
+#![allow(clippy::ptr_arg)]
+
+use crate::error::{invalid_char, Result};
+use crate::geometry::Direction;
+use crate::params::DayParams;
+use crate::search;
+use crate::solution::{Answer, Solution};
+use serde::Deserialize;
+
+/// How many layers of directional keypads are between the person and the numeric keypad in part 1
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub start_layer: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        return Params { start_layer: 2 };
+    }
+}
+
 const NUM_LEN: usize = 11;
 #[derive(Clone,Copy,PartialEq,Debug)]
-enum Num {
+pub enum Num {
     Zero,
     One,
     Two,
@@ -14,31 +36,29 @@ enum Num {
     A,
 }
 
+// Button on a directional keypad, an arrow or `A` to press the button the robot's arm is on
 #[derive(Clone,Copy,PartialEq,Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+enum DirKey {
+    Arrow(Direction),
     A,
-    Invalid,
 }
 
+#[allow(dead_code)]
 const DIR_LEN: usize = 5;
-fn char_to_num(num: char) -> Num {
+fn char_to_num(num: char) -> Option<Num> {
     return match num {
-        '0' => Num::Zero,
-        '1' => Num::One,
-        '2' => Num::Two,
-        '3' => Num::Three,
-        '4' => Num::Four,
-        '5' => Num::Five,
-        '6' => Num::Six,
-        '7' => Num::Seven,
-        '8' => Num::Eight,
-        '9' => Num::Nine,
-        'A' => Num::A,
-        _ => panic!("Invalid number"),
+        '0' => Some(Num::Zero),
+        '1' => Some(Num::One),
+        '2' => Some(Num::Two),
+        '3' => Some(Num::Three),
+        '4' => Some(Num::Four),
+        '5' => Some(Num::Five),
+        '6' => Some(Num::Six),
+        '7' => Some(Num::Seven),
+        '8' => Some(Num::Eight),
+        '9' => Some(Num::Nine),
+        'A' => Some(Num::A),
+        _ => None,
     }
 }
 
@@ -60,106 +80,99 @@ fn num_to_idx(num: Num) -> usize {
 
 const IDX_TO_NUM: [Num;NUM_LEN] = [Num::Zero, Num::One, Num::Two, Num::Three, Num::Four, Num::Five, Num::Six, Num::Seven, Num::Eight, Num::Nine, Num::A];
 
-fn create_numpad() -> [[Direction; NUM_LEN]; NUM_LEN] {
-    let mut ret: [[Direction; NUM_LEN]; NUM_LEN] = [[Direction::Invalid; NUM_LEN]; NUM_LEN];
+// Direction from each button to each of its neighbours, or `None` if they are not next to each other
+fn create_numpad() -> [[Option<Direction>; NUM_LEN]; NUM_LEN] {
+    let mut ret: [[Option<Direction>; NUM_LEN]; NUM_LEN] = [[None; NUM_LEN]; NUM_LEN];
     // A
-    ret[num_to_idx(Num::A)][num_to_idx(Num::Zero)] = Direction::Left;
-    ret[num_to_idx(Num::A)][num_to_idx(Num::Three)] = Direction::Up;
+    ret[num_to_idx(Num::A)][num_to_idx(Num::Zero)] = Some(Direction::West);
+    ret[num_to_idx(Num::A)][num_to_idx(Num::Three)] = Some(Direction::North);
     // 0
-    ret[num_to_idx(Num::Zero)][num_to_idx(Num::A)] = Direction::Right;
-    ret[num_to_idx(Num::Zero)][num_to_idx(Num::Two)] = Direction::Up;
+    ret[num_to_idx(Num::Zero)][num_to_idx(Num::A)] = Some(Direction::East);
+    ret[num_to_idx(Num::Zero)][num_to_idx(Num::Two)] = Some(Direction::North);
     // 1
-    ret[num_to_idx(Num::One)][num_to_idx(Num::Four)] = Direction::Up;
-    ret[num_to_idx(Num::One)][num_to_idx(Num::Two)] = Direction::Right;
+    ret[num_to_idx(Num::One)][num_to_idx(Num::Four)] = Some(Direction::North);
+    ret[num_to_idx(Num::One)][num_to_idx(Num::Two)] = Some(Direction::East);
     // 2
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::One)] = Direction::Left;
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::Three)] = Direction::Right;
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::Five)] = Direction::Up;
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::Zero)] = Direction::Down;
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::One)] = Some(Direction::West);
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::Three)] = Some(Direction::East);
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::Five)] = Some(Direction::North);
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::Zero)] = Some(Direction::South);
     // 3
-    ret[num_to_idx(Num::Three)][num_to_idx(Num::Two)] = Direction::Left;
-    ret[num_to_idx(Num::Three)][num_to_idx(Num::Six)] = Direction::Up;
-    ret[num_to_idx(Num::Three)][num_to_idx(Num::A)] = Direction::Down;
+    ret[num_to_idx(Num::Three)][num_to_idx(Num::Two)] = Some(Direction::West);
+    ret[num_to_idx(Num::Three)][num_to_idx(Num::Six)] = Some(Direction::North);
+    ret[num_to_idx(Num::Three)][num_to_idx(Num::A)] = Some(Direction::South);
     // 4
-    ret[num_to_idx(Num::Four)][num_to_idx(Num::One)] = Direction::Down;
-    ret[num_to_idx(Num::Four)][num_to_idx(Num::Five)] = Direction::Right;
-    ret[num_to_idx(Num::Four)][num_to_idx(Num::Seven)] = Direction::Up;
+    ret[num_to_idx(Num::Four)][num_to_idx(Num::One)] = Some(Direction::South);
+    ret[num_to_idx(Num::Four)][num_to_idx(Num::Five)] = Some(Direction::East);
+    ret[num_to_idx(Num::Four)][num_to_idx(Num::Seven)] = Some(Direction::North);
     // 5
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Two)] = Direction::Down;
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Four)] = Direction::Left;
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Six)] = Direction::Right;
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Eight)] = Direction::Up;
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Two)] = Some(Direction::South);
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Four)] = Some(Direction::West);
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Six)] = Some(Direction::East);
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Eight)] = Some(Direction::North);
     // 6
-    ret[num_to_idx(Num::Six)][num_to_idx(Num::Three)] = Direction::Down;
-    ret[num_to_idx(Num::Six)][num_to_idx(Num::Five)] = Direction::Left;
-    ret[num_to_idx(Num::Six)][num_to_idx(Num::Nine)] = Direction::Up;
+    ret[num_to_idx(Num::Six)][num_to_idx(Num::Three)] = Some(Direction::South);
+    ret[num_to_idx(Num::Six)][num_to_idx(Num::Five)] = Some(Direction::West);
+    ret[num_to_idx(Num::Six)][num_to_idx(Num::Nine)] = Some(Direction::North);
     // 7
-    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Four)] = Direction::Down;
-    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Eight)] = Direction::Right;
+    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Four)] = Some(Direction::South);
+    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Eight)] = Some(Direction::East);
     // 8
-    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Five)] = Direction::Down;
-    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Seven)] = Direction::Left;
-    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Nine)] = Direction::Right;
+    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Five)] = Some(Direction::South);
+    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Seven)] = Some(Direction::West);
+    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Nine)] = Some(Direction::East);
     // 9
-    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Six)] = Direction::Down;
-    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Eight)] = Direction::Left;
+    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Six)] = Some(Direction::South);
+    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Eight)] = Some(Direction::West);
     return ret;
 }
 
-fn parse_contents(contents: &String) -> Vec<Vec<Num>> {
+fn parse_contents(contents: &str) -> Result<Vec<Vec<Num>>> {
     let mut ret = vec![];
     for line in contents.lines() {
         let mut nums = vec![];
-        for c in line.chars() {
-            nums.push(char_to_num(c));
+        for (i, c) in line.chars().enumerate() {
+            let Some(num) = char_to_num(c) else {
+                return Err(invalid_char(contents, line, i, c, "keypad button"));
+            };
+            nums.push(num);
         }
         ret.push(nums);
     }
-    return ret;
+    return Ok(ret);
 }
 
-fn get_num_graph(numpad: &[[Direction; NUM_LEN]; NUM_LEN]) -> Vec<Vec<usize>> {
-    let each_num_fcn = |x: &[Direction;NUM_LEN]| {
-        return (0..NUM_LEN).filter(|&i| x[i] != Direction::Invalid).collect::<Vec<usize>>();
+fn get_num_graph(numpad: &[[Option<Direction>; NUM_LEN]; NUM_LEN]) -> Vec<Vec<usize>> {
+    let each_num_fcn = |x: &[Option<Direction>;NUM_LEN]| {
+        return (0..NUM_LEN).filter(|&i| x[i].is_some()).collect::<Vec<usize>>();
     };
-    // For each el in NUMPAD, collect all idxs that are not Direction::INVALID
+    // For each el in NUMPAD, collect all idxs that are neighbours
     return numpad.iter().map(each_num_fcn).collect();
 }
 
-fn get_num_dists(numpad: &[[Direction; NUM_LEN]; NUM_LEN], numpad_graph: &Vec<Vec<usize>>, from: Num) -> [Vec<Direction>; NUM_LEN] {
-    // Get the distance from one number to all others using Dijkstra
-    let mut distances = core::array::from_fn(|_|  vec![Direction::Invalid; NUM_LEN+1]); // (path)
-    let mut queue = vec![from];
-    distances[num_to_idx(from)] = Vec::new();
-    // println!("FROM: {}: {:?}", num_to_idx(from), from);
-    while let Some(u) = queue.pop() {
-        let u_idx = num_to_idx(u);
-        let u_hist = distances[u_idx].clone();
-        for &neighbor in numpad_graph[u_idx].iter() {
-            if u_hist.len() + 1 < distances[neighbor].len() {
-                let mut new_hist = u_hist.clone();
-                new_hist.push(numpad[u_idx][neighbor]);
-                distances[neighbor] = new_hist;
-                queue.push(IDX_TO_NUM[neighbor]);
-            }
-        }
-    }
-    return distances;
+fn get_num_routes(numpad: &[[Option<Direction>; NUM_LEN]; NUM_LEN], numpad_graph: &Vec<Vec<usize>>, from: Num) -> [Vec<Vec<Direction>>; NUM_LEN] {
+    // Get every shortest route from one number to all others, as the arrows to press, using a breadth-first search
+    log::trace!("FROM: {}: {:?}", num_to_idx(from), from);
+    let found = search::bfs(num_to_idx(from), |&u| numpad_graph[u].iter().copied(), |_| false);
+    return core::array::from_fn(|to| {
+        let paths = found.all_paths(&to);
+        return paths.iter().map(|path| path.windows(2).filter_map(|step| numpad[step[0]][step[1]]).collect()).collect();
+    });
 }
 
-fn get_all_num_dists() -> [[Vec<Direction>;NUM_LEN];NUM_LEN] {
+fn get_all_num_routes() -> [[Vec<Vec<Direction>>;NUM_LEN];NUM_LEN] {
     let numpad  = create_numpad();
     let numpad_graph = get_num_graph(&numpad);
-    let mut all_num_dists = core::array::from_fn(|_| core::array::from_fn(|_| Vec::new()));
+    let mut all_num_routes = core::array::from_fn(|_| core::array::from_fn(|_| Vec::new()));
     for i in 0..NUM_LEN {
         let num = IDX_TO_NUM[i];
-        let num_dists = get_num_dists(&numpad, &numpad_graph, num);
+        let num_routes = get_num_routes(&numpad, &numpad_graph, num);
         for j in 0..NUM_LEN {
-            println!("{:?} -> {:?}: {}, {:?}", num, IDX_TO_NUM[j], num_dists[j].len(), num_dists[j]);
-            all_num_dists[i][j] = num_dists[j].clone();
+            log::trace!("{:?} -> {:?}: {} route(s), {:?}", num, IDX_TO_NUM[j], num_routes[j].len(), num_routes[j]);
+            all_num_routes[i][j] = num_routes[j].clone();
         }
     }
-    return all_num_dists;
+    return all_num_routes;
 }
 
 fn nums_to_usize(nums: &Vec<Num>) -> usize {
@@ -172,117 +185,137 @@ fn nums_to_usize(nums: &Vec<Num>) -> usize {
     return ret;
 }
 
-fn print_dir(dir: Direction) {
-    match dir {
-        Direction::Up => print!("^"),
-        Direction::Down => print!("v"),
-        Direction::Left => print!("<"),
-        Direction::Right => print!(">"),
-        Direction::A => print!("A"),
-        _ => print!("X"),
-    }
+fn dir_char(key: DirKey) -> char {
+    return match key {
+        DirKey::Arrow(dir) => dir.arrow(),
+        DirKey::A => 'A',
+    };
 }
 
-fn human_fixed_point(dir: Direction) -> Vec<Direction> {
-    print_dir(dir);
-    return vec![dir];
+#[allow(dead_code)]
+fn human_fixed_point(key: DirKey) -> Vec<DirKey> {
+    log::trace!("{}", dir_char(key));
+    return vec![key];
 }
 
-fn dirpad_path(from: Direction, to: Direction) -> Vec<Direction> {
-    let ret =  match from {
-        Direction::A => match to {
-            Direction::Up => vec![Direction::Left],
-            Direction::Down => vec![Direction::Left, Direction::Down],
-            Direction::Left => vec![Direction::Down, Direction::Left, Direction::Left],
-            Direction::Right => vec![Direction::Down],
-            Direction::A => vec![],
-            _ => vec![Direction::Invalid],
-        },
-        Direction::Up => match to {
-            Direction::Up => vec![],
-            Direction::Down => vec![Direction::Down],
-            Direction::Left => vec![Direction::Down, Direction::Left],
-            Direction::Right => vec![Direction::Down, Direction::Right],
-            Direction::A => vec![Direction::Right],
-            _ => vec![Direction::Invalid],
-        },
-        Direction::Down => match to {
-            Direction::Up => vec![Direction::Up],
-            Direction::Down => vec![],
-            Direction::Left => vec![Direction::Left],
-            Direction::Right => vec![Direction::Right],
-            Direction::A => vec![Direction::Up, Direction::Right],
-            _ => vec![Direction::Invalid],
-        },
-        Direction::Left => match to {
-            Direction::Up => vec![Direction::Right, Direction::Up],
-            Direction::Down => vec![Direction::Right],
-            Direction::Left => vec![],
-            Direction::Right => vec![Direction::Right, Direction::Right],
-            Direction::A => vec![Direction::Right, Direction::Right, Direction::Up],
-            _ => vec![Direction::Invalid],
-        },
-        Direction::Right => match to {
-            Direction::Up => vec![Direction::Left, Direction::Up],
-            Direction::Down => vec![Direction::Left],
-            Direction::Left => vec![Direction::Left, Direction::Left],
-            Direction::Right => vec![],
-            Direction::A => vec![Direction::Up],
-            _ => vec![Direction::Invalid],
-        },
-        _ => vec![Direction::Invalid],
+fn dirpad_path(from: DirKey, to: DirKey) -> Vec<Direction> {
+    // Arrows that move the arm from one button to another, without going over the gap at the top left
+    let arrows = match (dir_char(from), dir_char(to)) {
+        ('A', '^') => "<",
+        ('A', 'v') => "<v",
+        ('A', '<') => "v<<",
+        ('A', '>') => "v",
+        ('^', 'v') => "v",
+        ('^', '<') => "v<",
+        ('^', '>') => "v>",
+        ('^', 'A') => ">",
+        ('v', '^') => "^",
+        ('v', '<') => "<",
+        ('v', '>') => ">",
+        ('v', 'A') => "^>",
+        ('<', '^') => ">^",
+        ('<', 'v') => ">",
+        ('<', '>') => ">>",
+        ('<', 'A') => ">>^",
+        ('>', '^') => "<^",
+        ('>', 'v') => "<",
+        ('>', '<') => "<<",
+        ('>', 'A') => "^",
+        // Already on the button
+        _ => "",
     };
-    return ret;
+    return arrows.chars().filter_map(Direction::from_arrow).collect();
 }
 
-fn fixed_point(from: Direction, to: Direction, layer: usize) -> Vec<Direction> {
+// Buttons to press on the outermost keypad to move the arm `layer` keypads in from `from` to `to` and press it.
+// Every press there is the arm one keypad out moving from where it was and pressing `A` at the end.
+fn fixed_point(from: DirKey, to: DirKey, layer: usize) -> Vec<DirKey> {
     if layer == 0 {
         return vec![to];
     } else {
         let direct_path = dirpad_path(from, to);
-        let mut prev_dir = from;
+        let keys = direct_path.into_iter().map(DirKey::Arrow).chain([DirKey::A]);
+        let mut prev_dir = DirKey::A;
         let mut ret = vec![];
-        for &dir in &direct_path {
-            let mut path = fixed_point(prev_dir, dir, layer - 1);
+        for key in keys {
+            let mut path = fixed_point(prev_dir, key, layer - 1);
             ret.append(&mut path);
-            prev_dir = dir;
+            prev_dir = key;
         }
         return ret;
     }
 }
 
-fn part1(codes: &Vec<Vec<Num>>) -> usize {
-    const START_LAYER: usize = 2;
-    let all_num_dists = get_all_num_dists();
+// Buttons to press on the outermost keypad to type a route on the keypad `layer` keypads in, ending with `A`
+fn route_presses(route: &Vec<Direction>, layer: usize) -> Vec<DirKey> {
+    let keys = route.iter().map(|&dir| DirKey::Arrow(dir)).chain([DirKey::A]);
+    let mut prev_dir = DirKey::A;
+    let mut ret = vec![];
+    for key in keys {
+        ret.append(&mut fixed_point(prev_dir, key, layer));
+        prev_dir = key;
+    }
+    return ret;
+}
+
+fn part1(codes: &Vec<Vec<Num>>, start_layer: usize) -> usize {
+    let all_num_routes = get_all_num_routes();
     let mut total_dist = 0;
-    println!();
     for code in codes {
         let mut dist = 0;
         let mut prev_num = Num::A;
-        let mut prev_dir = Direction::A;
         for &n in code {
-            print!("{:?} -> {:?}: ", prev_num, n);
-            let mut dirs = all_num_dists[num_to_idx(prev_num)][num_to_idx(n)].clone();
-            print!("{:?} || ", dirs);
-            for &dir in &dirs {
-                let mut path = fixed_point(prev_dir, dir, START_LAYER);
-                for &p in &path {
-                    print_dir(p);
-                }
-                dist += path.len();
-                prev_dir = dir;
-            }
+            // Routes that are as short on the numeric keypad can take different numbers of presses to type
+            let routes = &all_num_routes[num_to_idx(prev_num)][num_to_idx(n)];
+            let presses = routes.iter().map(|route| route_presses(route, start_layer)).min_by_key(Vec::len).unwrap_or_default();
+            log::trace!("{:?} -> {:?}: {} route(s) || {}", prev_num, n, routes.len(), presses.iter().map(|&p| dir_char(p)).collect::<String>());
+            dist += presses.len();
             prev_num = n;
-            println!();
         }
-        println!("\n{}*{}", dist, nums_to_usize(code));
+        log::debug!("{}*{}", dist, nums_to_usize(code));
         total_dist += dist*nums_to_usize(code);
     }
     return total_dist;
 }
 
-pub fn fcn(contents: &String) {
-    let codes = parse_contents(contents);
-    let p1 = part1(&codes);
-    println!("Part 1: {}", p1);
-}
\ No newline at end of file
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = (Params, Vec<Vec<Num>>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return self.parse_with(contents, &DayParams::default());
+    }
+
+    fn parse_with(&self, contents: &str, params: &DayParams) -> Result<Self::Input> {
+        return Ok((params.get()?, parse_contents(contents)?));
+    }
+
+    fn part1(&self, (params, codes): &Self::Input) -> Result<Answer> {
+        return Ok(part1(codes, params.start_layer).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "029A
+980A
+179A
+456A
+379A
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day21.parse(EXAMPLE).unwrap();
+        assert_eq!(Day21.part1(&input).unwrap().to_string(), "126384");
+    }
+
+    #[test]
+    fn code_values() {
+        let codes = parse_contents(EXAMPLE).unwrap();
+        assert_eq!(codes.iter().map(nums_to_usize).collect::<Vec<_>>(), vec![29, 980, 179, 456, 379]);
+    }
+}
@@ -1,6 +1,13 @@
+#![allow(clippy::ptr_arg)]
+
+use crate::error::{invalid_char, Result};
+use crate::geometry::Direction;
+use crate::search;
+use crate::solution::{Answer, Solution};
+
 const NUM_LEN: usize = 11;
 #[derive(Clone,Copy,PartialEq,Debug)]
-enum Num {
+pub enum Num {
     Zero,
     One,
     Two,
@@ -14,30 +21,32 @@ enum Num {
     A,
 }
 
+// Button on a directional keypad, an arrow or `A` to press the button the robot's arm is on
 #[derive(Clone,Copy,PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+enum DirKey {
+    Arrow(Direction),
     A,
-    Invalid,
 }
 
-fn char_to_num(num: char) -> Num {
+const UP: DirKey = DirKey::Arrow(Direction::North);
+const DOWN: DirKey = DirKey::Arrow(Direction::South);
+const LEFT: DirKey = DirKey::Arrow(Direction::West);
+const RIGHT: DirKey = DirKey::Arrow(Direction::East);
+
+fn char_to_num(num: char) -> Option<Num> {
     return match num {
-        '0' => Num::Zero,
-        '1' => Num::One,
-        '2' => Num::Two,
-        '3' => Num::Three,
-        '4' => Num::Four,
-        '5' => Num::Five,
-        '6' => Num::Six,
-        '7' => Num::Seven,
-        '8' => Num::Eight,
-        '9' => Num::Nine,
-        'A' => Num::A,
-        _ => panic!("Invalid number"),
+        '0' => Some(Num::Zero),
+        '1' => Some(Num::One),
+        '2' => Some(Num::Two),
+        '3' => Some(Num::Three),
+        '4' => Some(Num::Four),
+        '5' => Some(Num::Five),
+        '6' => Some(Num::Six),
+        '7' => Some(Num::Seven),
+        '8' => Some(Num::Eight),
+        '9' => Some(Num::Nine),
+        'A' => Some(Num::A),
+        _ => None,
     }
 }
 
@@ -60,151 +69,132 @@ fn num_to_idx(num: Num) -> usize {
 const IDX_TO_NUM: [Num;NUM_LEN] = [Num::Zero, Num::One, Num::Two, Num::Three, Num::Four, Num::Five, Num::Six, Num::Seven, Num::Eight, Num::Nine, Num::A];
 
 #[allow(dead_code)]
-fn dir_to_idx(dir: Direction) -> usize {
-    return match dir {
-        Direction::Up => 0,
-        Direction::Down => 1,
-        Direction::Left => 2,
-        Direction::Right => 3,
-        Direction::A => 4,
-        _ => panic!("Invalid direction"),
+fn dir_to_idx(key: DirKey) -> usize {
+    return match key {
+        DirKey::Arrow(Direction::North) => 0,
+        DirKey::Arrow(Direction::South) => 1,
+        DirKey::Arrow(Direction::West) => 2,
+        DirKey::Arrow(Direction::East) => 3,
+        DirKey::A => 4,
     }
 }
 
-fn create_numpad() -> [[Direction; NUM_LEN]; NUM_LEN] {
-    let mut ret: [[Direction; NUM_LEN]; NUM_LEN] = [[Direction::Invalid; NUM_LEN]; NUM_LEN];
+// Arrow from each button to each of its neighbours, or `None` if they are not next to each other
+fn create_numpad() -> [[Option<DirKey>; NUM_LEN]; NUM_LEN] {
+    let mut ret: [[Option<DirKey>; NUM_LEN]; NUM_LEN] = [[None; NUM_LEN]; NUM_LEN];
     // A
-    ret[num_to_idx(Num::A)][num_to_idx(Num::Zero)] = Direction::Left;
-    ret[num_to_idx(Num::A)][num_to_idx(Num::Three)] = Direction::Up;
+    ret[num_to_idx(Num::A)][num_to_idx(Num::Zero)] = Some(LEFT);
+    ret[num_to_idx(Num::A)][num_to_idx(Num::Three)] = Some(UP);
     // 0
-    ret[num_to_idx(Num::Zero)][num_to_idx(Num::A)] = Direction::Right;
-    ret[num_to_idx(Num::Zero)][num_to_idx(Num::Two)] = Direction::Up;
+    ret[num_to_idx(Num::Zero)][num_to_idx(Num::A)] = Some(RIGHT);
+    ret[num_to_idx(Num::Zero)][num_to_idx(Num::Two)] = Some(UP);
     // 1
-    ret[num_to_idx(Num::One)][num_to_idx(Num::Four)] = Direction::Up;
-    ret[num_to_idx(Num::One)][num_to_idx(Num::Two)] = Direction::Right;
+    ret[num_to_idx(Num::One)][num_to_idx(Num::Four)] = Some(UP);
+    ret[num_to_idx(Num::One)][num_to_idx(Num::Two)] = Some(RIGHT);
     // 2
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::One)] = Direction::Left;
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::Three)] = Direction::Right;
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::Five)] = Direction::Up;
-    ret[num_to_idx(Num::Two)][num_to_idx(Num::Zero)] = Direction::Down;
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::One)] = Some(LEFT);
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::Three)] = Some(RIGHT);
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::Five)] = Some(UP);
+    ret[num_to_idx(Num::Two)][num_to_idx(Num::Zero)] = Some(DOWN);
     // 3
-    ret[num_to_idx(Num::Three)][num_to_idx(Num::Two)] = Direction::Left;
-    ret[num_to_idx(Num::Three)][num_to_idx(Num::Six)] = Direction::Up;
-    ret[num_to_idx(Num::Three)][num_to_idx(Num::A)] = Direction::Down;
+    ret[num_to_idx(Num::Three)][num_to_idx(Num::Two)] = Some(LEFT);
+    ret[num_to_idx(Num::Three)][num_to_idx(Num::Six)] = Some(UP);
+    ret[num_to_idx(Num::Three)][num_to_idx(Num::A)] = Some(DOWN);
     // 4
-    ret[num_to_idx(Num::Four)][num_to_idx(Num::One)] = Direction::Down;
-    ret[num_to_idx(Num::Four)][num_to_idx(Num::Five)] = Direction::Right;
-    ret[num_to_idx(Num::Four)][num_to_idx(Num::Seven)] = Direction::Up;
+    ret[num_to_idx(Num::Four)][num_to_idx(Num::One)] = Some(DOWN);
+    ret[num_to_idx(Num::Four)][num_to_idx(Num::Five)] = Some(RIGHT);
+    ret[num_to_idx(Num::Four)][num_to_idx(Num::Seven)] = Some(UP);
     // 5
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Two)] = Direction::Down;
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Four)] = Direction::Left;
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Six)] = Direction::Right;
-    ret[num_to_idx(Num::Five)][num_to_idx(Num::Eight)] = Direction::Up;
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Two)] = Some(DOWN);
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Four)] = Some(LEFT);
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Six)] = Some(RIGHT);
+    ret[num_to_idx(Num::Five)][num_to_idx(Num::Eight)] = Some(UP);
     // 6
-    ret[num_to_idx(Num::Six)][num_to_idx(Num::Three)] = Direction::Down;
-    ret[num_to_idx(Num::Six)][num_to_idx(Num::Five)] = Direction::Left;
-    ret[num_to_idx(Num::Six)][num_to_idx(Num::Nine)] = Direction::Up;
+    ret[num_to_idx(Num::Six)][num_to_idx(Num::Three)] = Some(DOWN);
+    ret[num_to_idx(Num::Six)][num_to_idx(Num::Five)] = Some(LEFT);
+    ret[num_to_idx(Num::Six)][num_to_idx(Num::Nine)] = Some(UP);
     // 7
-    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Four)] = Direction::Down;
-    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Eight)] = Direction::Right;
+    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Four)] = Some(DOWN);
+    ret[num_to_idx(Num::Seven)][num_to_idx(Num::Eight)] = Some(RIGHT);
     // 8
-    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Five)] = Direction::Down;
-    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Seven)] = Direction::Left;
-    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Nine)] = Direction::Right;
+    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Five)] = Some(DOWN);
+    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Seven)] = Some(LEFT);
+    ret[num_to_idx(Num::Eight)][num_to_idx(Num::Nine)] = Some(RIGHT);
     // 9
-    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Six)] = Direction::Down;
-    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Eight)] = Direction::Left;
+    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Six)] = Some(DOWN);
+    ret[num_to_idx(Num::Nine)][num_to_idx(Num::Eight)] = Some(LEFT);
     return ret;
 }
 
-fn parse_contents(contents: &String) -> Vec<Vec<Num>> {
+fn parse_contents(contents: &str) -> Result<Vec<Vec<Num>>> {
     let mut ret = vec![];
     for line in contents.lines() {
         let mut nums = vec![];
-        for c in line.chars() {
-            nums.push(char_to_num(c));
+        for (i, c) in line.chars().enumerate() {
+            let Some(num) = char_to_num(c) else {
+                return Err(invalid_char(contents, line, i, c, "keypad button"));
+            };
+            nums.push(num);
         }
         ret.push(nums);
     }
-    return ret;
+    return Ok(ret);
 }
 
-fn dist_from_a_me(to: Direction) -> usize {
+fn dist_from_a_me(_to: DirKey) -> usize {
     // How many keystrokes to input a given direction
     // Starting position is A
     // Includes punching in A at the end
-    // match to {
-    //     Direction::Up => print!("^"), // Left -> A
-    //     Direction::Down => print!("v"), // Left -> Down -> A
-    //     Direction::Left => print!("<"), // Left -> Down -> Left -> A
-    //     Direction::Right => print!(">"), // Down -> A
-    //     Direction::A => print!("A"), // A
-    //     _ => panic!("Invalid direction!"), // Invalid
-    // }
     return 1;
 }
 
-fn dist_from_a_close(to: Direction) -> usize {
+fn dist_from_a_close(to: DirKey) -> usize {
     // How many keystrokes to input a given direction
     // Starting position is A
     // Includes punching in A at the end
     return match to {
-        Direction::Up => dist_from_a_me(Direction::Left) + dist_from_a_me(Direction::A), // Left -> A
-        Direction::Down => dist_from_a_me(Direction::Left) + dist_from_a_me(Direction::Down) + dist_from_a_me(Direction::A), // Left -> Down -> A
-        Direction::Left => dist_from_a_me(Direction::Left) + dist_from_a_me(Direction::Down) + dist_from_a_me(Direction::Left) + dist_from_a_me(Direction::A), // Left -> Down -> Left -> A
-        Direction::Right => dist_from_a_me(Direction::Down) + dist_from_a_me(Direction::A), // Down -> A
-        Direction::A => dist_from_a_me(Direction::A), // A
-        _ => usize::MAX, // Invalid
+        DirKey::Arrow(Direction::North) => dist_from_a_me(LEFT) + dist_from_a_me(DirKey::A), // Left -> A
+        DirKey::Arrow(Direction::South) => dist_from_a_me(LEFT) + dist_from_a_me(DOWN) + dist_from_a_me(DirKey::A), // Left -> Down -> A
+        DirKey::Arrow(Direction::West) => dist_from_a_me(LEFT) + dist_from_a_me(DOWN) + dist_from_a_me(LEFT) + dist_from_a_me(DirKey::A), // Left -> Down -> Left -> A
+        DirKey::Arrow(Direction::East) => dist_from_a_me(DOWN) + dist_from_a_me(DirKey::A), // Down -> A
+        DirKey::A => dist_from_a_me(DirKey::A), // A
     }
 }
 
-fn dist_from_a_far(dir: Direction) -> usize {
+fn dist_from_a_far(key: DirKey) -> usize {
     // Get the number of keystrokes to go from A to another direction
-    return match dir {
-        Direction::A => dist_from_a_close(Direction::A),
-        Direction::Up => dist_from_a_close(Direction::Left) + dist_from_a_close(Direction::A),
-        Direction::Down => dist_from_a_close(Direction::Left) + dist_from_a_close(Direction::Down) + dist_from_a_close(Direction::A),
-        Direction::Left => dist_from_a_close(Direction::Left) + dist_from_a_close(Direction::Down) + dist_from_a_close(Direction::Left) + dist_from_a_close(Direction::A),
-        Direction::Right => dist_from_a_close(Direction::Down) + dist_from_a_close(Direction::A),
-        _ => usize::MAX,
+    return match key {
+        DirKey::A => dist_from_a_close(DirKey::A),
+        DirKey::Arrow(Direction::North) => dist_from_a_close(LEFT) + dist_from_a_close(DirKey::A),
+        DirKey::Arrow(Direction::South) => dist_from_a_close(LEFT) + dist_from_a_close(DOWN) + dist_from_a_close(DirKey::A),
+        DirKey::Arrow(Direction::West) => dist_from_a_close(LEFT) + dist_from_a_close(DOWN) + dist_from_a_close(LEFT) + dist_from_a_close(DirKey::A),
+        DirKey::Arrow(Direction::East) => dist_from_a_close(DOWN) + dist_from_a_close(DirKey::A),
     }
 }
 
 
-fn get_num_neighbor_dist(from: usize, to: usize, numpad: [[Direction;NUM_LEN];NUM_LEN]) -> usize {
+fn get_num_neighbor_dist(from: usize, to: usize, numpad: [[Option<DirKey>;NUM_LEN];NUM_LEN]) -> usize {
     // Get the distance from one number to another
-    return dist_from_a_far(numpad[from][to]);
+    return numpad[from][to].map_or(usize::MAX, dist_from_a_far);
 }
 
-fn get_num_graph(numpad: [[Direction; NUM_LEN]; NUM_LEN]) -> Vec<Vec<usize>> {
-    let each_num_fcn = |x: &[Direction;NUM_LEN]| {
-        return (0..NUM_LEN).filter(|&i| x[i] != Direction::Invalid).collect::<Vec<usize>>();
+fn get_num_graph(numpad: [[Option<DirKey>; NUM_LEN]; NUM_LEN]) -> Vec<Vec<usize>> {
+    let each_num_fcn = |x: &[Option<DirKey>;NUM_LEN]| {
+        return (0..NUM_LEN).filter(|&i| x[i].is_some()).collect::<Vec<usize>>();
     };
-    // For each el in NUMPAD, collect all idxs that are not Direction::INVALID
+    // For each el in NUMPAD, collect all idxs that are neighbours
     return numpad.iter().map(each_num_fcn).collect();
 }
 
-fn get_num_dists(numpad_graph: &Vec<Vec<usize>>, from: Num, numpad: [[Direction; NUM_LEN]; NUM_LEN]) -> [usize; NUM_LEN] {
+fn get_num_dists(numpad_graph: &Vec<Vec<usize>>, from: Num, numpad: [[Option<DirKey>; NUM_LEN]; NUM_LEN]) -> [usize; NUM_LEN] {
     // Get the distance from one number to all others using Dijkstra
-    let mut distances = core::array::from_fn(|_| (usize::MAX, Vec::new())); // (dist, path)
-    let mut queue = vec![from];
-    distances[num_to_idx(from)] = 1;
-    // println!("FROM: {}: {:?}", num_to_idx(from), from);
-    while let Some(u) = queue.pop() {
-        let u_idx = num_to_idx(u);
-        for &neighbor in numpad_graph[u_idx].iter() {
-            let dist = get_num_neighbor_dist(u_idx, neighbor, numpad);
-            if distances[u_idx] + dist < distances[neighbor] {
-                distances[neighbor] = distances[u_idx] + dist;
-                queue.push(IDX_TO_NUM[neighbor]);
-            }
-        }
-    }
-    return distances;
+    log::trace!("FROM: {}: {:?}", num_to_idx(from), from);
+    let neighbors = |&u: &usize| numpad_graph[u].iter().map(move |&v| (v, get_num_neighbor_dist(u, v, numpad)));
+    let found = search::dijkstra(num_to_idx(from), neighbors, |_| false);
+    // Distances count from 1, the keystroke that presses `from` itself
+    return core::array::from_fn(|to| found.cost(&to).map_or(usize::MAX, |dist| dist + 1));
 }
 
-
-
 fn get_all_num_dists() -> [[usize;NUM_LEN];NUM_LEN] {
     let numpad  = create_numpad();
     let numpad_graph = get_num_graph(numpad);
@@ -228,18 +218,26 @@ fn part1(codes: &Vec<Vec<Num>>) -> usize {
         let mut dist = 0;
         let mut prev_num = Num::A;
         for &n in code {
-            // println!("{:?} -> {:?}: {}", prev_num, n, all_num_dists[num_to_idx(prev_num)][num_to_idx(n)]);
+            log::trace!("{:?} -> {:?}: {}", prev_num, n, all_num_dists[num_to_idx(prev_num)][num_to_idx(n)]);
             dist += all_num_dists[num_to_idx(prev_num)][num_to_idx(n)];
             prev_num = n;
         }
-        println!("{}*{}", dist, nums_to_usize(code));
+        log::debug!("{}*{}", dist, nums_to_usize(code));
         total_dist += dist*nums_to_usize(code);
     }
     return total_dist;
 }
 
-pub fn fcn(contents: &String) {
-    let codes = parse_contents(contents);
-    let p1 = part1(&codes);
-    println!("Part 1: {}", p1);
-}
\ No newline at end of file
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Vec<Num>>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, codes: &Self::Input) -> Result<Answer> {
+        return Ok(part1(codes).into());
+    }
+}
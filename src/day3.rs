@@ -1,3 +1,5 @@
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
 use regex::Regex;
 
 fn part1_line(line: &str) -> i32{
@@ -19,18 +21,52 @@ fn part2_line(line: &str) -> i32 {
     return part1_line(&new_line);
 }
 
-fn part1(lines: &String) -> i32 {
-    return lines.lines().map(|line| part1_line(line)).sum();
+fn part1(lines: &str) -> i32 {
+    return lines.lines().map(part1_line).sum();
 }
 
-fn part2(lines: &String) -> i32 {
+fn part2(lines: &str) -> i32 {
     let input = lines.replace("\n","");
     return part2_line(input.as_str());
 }
 
-pub fn fcn(contents: &String) {
-    let result = part1(contents);
-    println!("Part 1: {}", result);
-    let result2 = part2(contents);
-    println!("Part 2: {}", result2);
-}
\ No newline at end of file
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return Ok(contents.to_string());
+    }
+
+    fn part1(&self, lines: &Self::Input) -> Result<Answer> {
+        return Ok(part1(lines).into());
+    }
+
+    fn part2(&self, lines: &Self::Input) -> Result<Answer> {
+        return Ok(part2(lines).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part1() {
+        let input = Day3.parse("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))").unwrap();
+        assert_eq!(Day3.part1(&input).unwrap().to_string(), "161");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day3.parse("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))").unwrap();
+        assert_eq!(Day3.part2(&input).unwrap().to_string(), "48");
+    }
+
+    #[test]
+    fn dont_spans_lines() {
+        let input = Day3.parse("mul(1,1)don't()\nmul(2,2)do()mul(3,3)").unwrap();
+        assert_eq!(Day3.part2(&input).unwrap().to_string(), "10");
+    }
+}
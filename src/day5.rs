@@ -1,43 +1,43 @@
+#![allow(clippy::type_complexity)]
+
+use crate::error::{parse_at, Error, Result};
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
 
 // Split contents into two sections based on where \n\n is located
 // The first section is a list of tuples of (i32, i32).
 // I return a HashMap taking in a number and returning numbers that correspond to it
 // The second section is a list of lists of i32
-fn parse_contents(contents: &String) -> (HashMap<i32, Vec<i32>>, Vec<Vec<i32>>) {
-    let sections: Vec<&str> = contents.split("\n\n").collect();
-    let first_section: Vec<(i32, i32)> = sections[0].lines().map(|line| {
-        let parts: Vec<&str> = line.split("|").collect();
-        let p1: i32 = parts[0].parse().unwrap();
-        let p2: i32 = parts[1].parse().unwrap();
-        return (p1, p2);
-    }).collect();
-    let second_section: Vec<Vec<i32>> = sections[1].lines().map(|line| {
-        return line.split(",").map(|num| num.parse().unwrap()).collect();
-    }).collect();
+fn parse_contents(contents: &str) -> Result<(HashMap<i32, Vec<i32>>, Vec<Vec<i32>>)> {
+    let Some((rules, updates)) = contents.split_once("\n\n") else {
+        return Err(Error::invalid("Expected the rules and the updates separated by an empty line"));
+    };
+    let first_section: Vec<(i32, i32)> = rules.lines().map(|line| {
+        let Some((p1, p2)) = line.split_once("|") else {
+            return Err(Error::parse_at(contents, line, "Expected a rule `x|y`"));
+        };
+        return Ok((parse_at(contents, p1)?, parse_at(contents, p2)?));
+    }).collect::<Result<_>>()?;
+    let second_section: Vec<Vec<i32>> = updates.lines().map(|line| {
+        return line.split(",").map(|num| parse_at(contents, num)).collect();
+    }).collect::<Result<_>>()?;
     // If first_section is (x, y), get list of all (y) corresponding to each (x)
     let mut first_section_nums: HashMap<i32, Vec<i32>> = HashMap::new();
     for (x, y) in first_section {
-        if first_section_nums.contains_key(&x) {
-            first_section_nums.get_mut(&x).unwrap().push(y);
-        } else {
-            first_section_nums.insert(x, vec![y]);
-        }
+        first_section_nums.entry(x).or_default().push(y);
     }
-    return (first_section_nums, second_section);
+    return Ok((first_section_nums, second_section));
 }
 
 fn check_valid_list(first_section: &HashMap<i32, Vec<i32>>, list: &[i32]) -> bool {
     // Recursively check validity of list
-    if list.len() < 1 {
+    if list.is_empty() {
         return true;
     }
     // Get the first number in the list
     let x = list[list.len() - 1];
     // Get all numbers that correspond to x
-    let y_list_option = first_section.get(&x);
-    if !y_list_option.is_none() {
-        let y_list = y_list_option.unwrap();
+    if let Some(y_list) = first_section.get(&x) {
         // Make sure no numbers in y_list are in the list
         for y in y_list {
             if list.contains(y) {
@@ -53,15 +53,13 @@ fn check_valid_list(first_section: &HashMap<i32, Vec<i32>>, list: &[i32]) -> boo
 #[allow(dead_code)]
 fn make_valid_list(first_section: &HashMap<i32, Vec<i32>>, list: &[i32]) {
     // Recursively make a valid list
-    if list.len() < 1 {
+    if list.is_empty() {
         return;
     }
     // Get the first number in the list
     let x = list[list.len() - 1];
     // Get all numbers that correspond to x
-    let y_list_option = first_section.get(&x);
-    if !y_list_option.is_none() {
-        let y_list = y_list_option.unwrap();
+    if let Some(y_list) = first_section.get(&x) {
         // Make sure no numbers in y_list are in the list
         for y in y_list {
             if let Some(index) = list.iter().position(|&num| num == *y) {
@@ -78,10 +76,10 @@ fn make_valid_list(first_section: &HashMap<i32, Vec<i32>>, list: &[i32]) {
     make_valid_list(first_section, &list[..list.len()-1]);
 }
 
-fn part1(first_section: &HashMap<i32, Vec<i32>>, second_section: Vec<Vec<i32>>) -> i32 {
+fn part1(first_section: &HashMap<i32, Vec<i32>>, second_section: &Vec<Vec<i32>>) -> i32 {
     let mut p1_count = 0;
     for list in second_section {
-        if check_valid_list(first_section, &list.as_slice()) {
+        if check_valid_list(first_section, list) {
             // Get middle element of list
             let middle_num = list[list.len() / 2];
             p1_count += middle_num;
@@ -90,8 +88,57 @@ fn part1(first_section: &HashMap<i32, Vec<i32>>, second_section: Vec<Vec<i32>>)
     return p1_count;
 }
 
-pub fn fcn(contents: &String) {
-    let (first_section_nums, second_section) = parse_contents(contents);
-    let p1 = part1(&first_section_nums, second_section);
-    println!("Part 1: {}", p1);
-}
\ No newline at end of file
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = (HashMap<i32, Vec<i32>>, Vec<Vec<i32>>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, (first_section_nums, second_section): &Self::Input) -> Result<Answer> {
+        return Ok(part1(first_section_nums, second_section).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day5.parse(EXAMPLE).unwrap();
+        assert_eq!(Day5.part1(&input).unwrap().to_string(), "143");
+    }
+}
@@ -1,243 +1,205 @@
-fn parse_contents(contents: &String)->(usize, Vec<Vec<bool>>, (usize,usize)) {
-    // Returns length of row, grid representing where # are, and location of carot ^
-    let lines = contents.lines();
-    let mut row_len = 0;
-    let mut first_line = true;
-    let mut grid = Vec::new();
-    let mut carot = (0,0);
-    for (row,line) in lines.enumerate() {
-        // Get the length of the first line
-        if first_line {
-            row_len = line.len();
-            first_line = false;
-        }
-        // Parse the line into a vector of bools
-        let mut row_vec = Vec::with_capacity(row_len);
-        for (col, c) in line.chars().enumerate() {
-            if c == '^' {
-                carot = (row, col);
-            }
-            row_vec.push(c == '#');
-        }
-        grid.push(row_vec);
-    }
-    return (row_len, grid, carot);
+use crate::budget;
+use crate::error::{Error, Result};
+use crate::geometry::Direction;
+use crate::grid::Grid;
+use crate::render::{self, Animation};
+use crate::solution::{Answer, Solution};
+use std::collections::HashSet;
+
+fn parse_contents(contents: &str) -> Result<(Grid<bool>, (usize,usize))> {
+    // Returns grid representing where # are, and location of carot ^
+    let map = Grid::parse_chars(contents)?;
+    let Some(carot) = map.find(&'^') else {
+        return Err(Error::invalid("No guard `^` in the map"));
+    };
+    return Ok((map.map(|&c| c == '#'), carot));
 }
 
-// Create enum for direction
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-fn fill_in_direction(grid: &Vec<Vec<bool>>, visit_grid: &mut Vec<Vec<bool>>, guard: (usize,usize), direction: &Direction) -> (bool, (usize, usize)) {
-    let mut row = guard.0;
-    let mut col = guard.1;
-    let mut exits = false;
+fn fill_in_direction(grid: &Grid<bool>, visit_grid: &mut Grid<bool>, guard: (usize,usize), direction: Direction) -> (bool, (usize, usize)) {
+    let mut pos = guard;
     loop {
         // Check if we have visited this spot
-        visit_grid[row][col] = true;
+        visit_grid[pos] = true;
 
         // Check if we go out of bounds
-        match direction {
-            Direction::Up => if row == 0 { exits = true },
-            Direction::Down => if row == grid.len()-1 { exits = true },
-            Direction::Left => if col == 0 { exits = true },
-            Direction::Right => if col == grid[0].len()-1 { exits = true },
-        }
-        if exits {
-            break;
-        }
+        let Some(next) = grid.step(pos, direction.offset()) else {
+            return (true, pos);
+        };
 
         // Check if we have hit a wall
-        let hit_wall;
-        match direction {
-            Direction::Up => hit_wall = grid[row-1][col],
-            Direction::Down => hit_wall = grid[row+1][col],
-            Direction::Left => hit_wall = grid[row][col-1],
-            Direction::Right => hit_wall = grid[row][col+1],
-        }
-        if hit_wall {
-            break;
+        if grid[next] {
+            return (false, pos);
         }
 
         // Move in the direction
-        match direction {
-            Direction::Up => row -= 1,
-            Direction::Down => row += 1,
-            Direction::Left => col -= 1,
-            Direction::Right => col += 1,
-        }
-
+        pos = next;
     }
-    return (exits, (row, col));
 }
 
-// Function to print the grid, dead code
-#[allow(dead_code)]
-fn print_grid(grid: &Vec<Vec<bool>>, visited: &Vec<Vec<bool>>, carot: (usize,usize), direction: &Direction) {
-    let mut grid_str = String::new();
-    for (row_idx, row) in grid.clone().into_iter().enumerate() {
-        for (col_idx,spot) in row.into_iter().enumerate() {
-            if (row_idx, col_idx) == carot {
-                match direction {
-                    Direction::Up => grid_str.push('^'),
-                    Direction::Down => grid_str.push('v'),
-                    Direction::Left => grid_str.push('<'),
-                    Direction::Right => grid_str.push('>'),
-                }
-            }
-            else if visited[row_idx][col_idx] {
-                grid_str.push('X');
-            } else if spot {
-                grid_str.push('#');
-            } else {
-                grid_str.push('.');
-            }
-        }
-        grid_str.push('\n');
-    }
-    println!("{}", grid_str);
+// Draw the grid with the visited spots and the guard, if it is still on the map, for tracing and rendering
+fn drawing(grid: &Grid<bool>, visited: &Grid<bool>, guard: Option<((usize,usize), Direction)>) -> Grid<char> {
+    return Grid::from_fn(grid.rows(), grid.cols(), |pos| {
+        if let Some((_, direction)) = guard.filter(|&(carot, _)| carot == pos) {
+            direction.arrow()
+        } else if visited[pos] {
+            'X'
+        } else if grid[pos] {
+            '#'
+        } else {
+            '.'
+        }
+    });
+}
+
+fn grid_string(grid: &Grid<bool>, visited: &Grid<bool>, carot: (usize,usize), direction: Direction) -> String {
+    return drawing(grid, visited, Some((carot, direction))).to_string();
 }
 
 
-fn part1(row_len: usize, grid: &Vec<Vec<bool>>, carot: (usize,usize)) -> (i32, Vec<Vec<bool>>) {
+fn part1(grid: &Grid<bool>, carot: (usize,usize), animation: &mut Animation) -> (i32, Grid<bool>) {
     // Create a grid to keep track of visited spots
-    let mut visit_grid = vec![vec![false; row_len]; grid.len()];
+    let mut visit_grid = Grid::new(grid.rows(), grid.cols(), false);
 
-    // Create the grid as a string for debugging
-    // print_grid(&grid, &visit_grid, carot, &Direction::Up);
+    log::trace!("Start:\n{}", grid_string(grid, &visit_grid, carot, Direction::North));
 
     // Start at the carot
     let mut guard = carot;
-    let mut direction = Direction::Up;
+    let mut direction = Direction::North;
     loop {
         // Fill in the direction
-        let (exits, new_guard) = fill_in_direction(&grid, &mut visit_grid, guard, &direction);
+        let (exits, new_guard) = fill_in_direction(grid, &mut visit_grid, guard, direction);
+        animation.frame(|| drawing(grid, &visit_grid, Some((new_guard, direction))), render::map_colors);
         if exits {
             break;
         }
 
         // Turn right
-        match direction {
-            Direction::Up => direction = Direction::Right,
-            Direction::Right => direction = Direction::Down,
-            Direction::Down => direction = Direction::Left,
-            Direction::Left => direction = Direction::Up,
-        }
+        direction = direction.turn_right();
 
         // Move to the new guard
         guard = new_guard;
-        // print_grid(&grid, &visit_grid, guard, &direction);
-        // break;
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("Turned at {:?}:\n{}", guard, grid_string(grid, &visit_grid, guard, direction));
+        }
     }
 
 
     // Finish by summing over all the visited spots
-    let mut count = 0;
-    for row in &visit_grid {
-        for visited in row {
-            count += *visited as i32;
-        }
-    }
+    let count = visit_grid.iter().filter(|(_, &visited)| visited).count() as i32;
     return (count, visit_grid);
 }
 
-fn go_in_direction(grid: &Vec<Vec<bool>>, carot: (usize, usize), direction: &Direction) -> (bool, (usize, usize)) {
-    let mut row = carot.0;
-    let mut col = carot.1;
-    let mut exits = false;
+fn go_in_direction(grid: &Grid<bool>, carot: (usize, usize), direction: Direction) -> (bool, (usize, usize)) {
+    let mut pos = carot;
     loop {
         // Check if we go out of bounds
-        match direction {
-            Direction::Up => if row == 0 { exits = true },
-            Direction::Down => if row == grid.len()-1 { exits = true },
-            Direction::Left => if col == 0 { exits = true },
-            Direction::Right => if col == grid[0].len()-1 { exits = true },
-        }
-        if exits {
-            break;
-        }
+        let Some(next) = grid.step(pos, direction.offset()) else {
+            return (true, pos);
+        };
 
         // Check if we have hit a wall
-        let hit_wall;
-        match direction {
-            Direction::Up => hit_wall = grid[row-1][col],
-            Direction::Down => hit_wall = grid[row+1][col],
-            Direction::Left => hit_wall = grid[row][col-1],
-            Direction::Right => hit_wall = grid[row][col+1],
-        }
-        if hit_wall {
-            break;
+        if grid[next] {
+            return (false, pos);
         }
 
         // Move in the direction
-        match direction {
-            Direction::Up => row -= 1,
-            Direction::Down => row += 1,
-            Direction::Left => col -= 1,
-            Direction::Right => col += 1,
-        }
-
+        pos = next;
     }
-    return (exits, (row, col));
 }
 
-fn is_loop(grid: &Vec<Vec<bool>>, carot: (usize, usize)) -> bool {
-    // Check if this grid induces a loop
+/// Check whether the guard starting at `carot` walks in a loop on `grid`
+pub fn is_loop(grid: &Grid<bool>, carot: (usize, usize)) -> Result<bool> {
+    // The guard is stuck in a loop once it turns at the same spot facing the same way twice.
+    // The loop does not have to pass through the starting position.
+    let mut turns = HashSet::new();
     // Start at the carot
     let mut guard = carot;
-    let mut direction = Direction::Up;
-    let mut ret = false;
+    let mut direction = Direction::North;
     loop {
+        budget::check()?;
         // Fill in the direction
-        let (exits, new_guard) = go_in_direction(grid, guard, &direction);
-        if new_guard == carot {
-            ret = true;
-            break;
-        }
-
+        let (exits, new_guard) = go_in_direction(grid, guard, direction);
         if exits {
-            break;
+            return Ok(false);
+        }
+        if !turns.insert((new_guard, direction)) {
+            return Ok(true);
         }
 
         // Turn right
-        match direction {
-            Direction::Up => direction = Direction::Right,
-            Direction::Right => direction = Direction::Down,
-            Direction::Down => direction = Direction::Left,
-            Direction::Left => direction = Direction::Up,
-        }
+        direction = direction.turn_right();
 
         // Move to the new guard
         guard = new_guard;
     }
-    return ret;
 }
 
-fn part2(grid: &Vec<Vec<bool>>, visited: &Vec<Vec<bool>>, carot: (usize, usize)) -> i32{
+fn part2(grid: &Grid<bool>, visited: &Grid<bool>, carot: (usize, usize)) -> Result<i32> {
     // Check every location that the carot can go to.
     // If you change grid to be true at that location, does it cause a loop?
     let mut count = 0;
-    for (row_idx, row) in grid.iter().enumerate() {
-        for (col_idx, _) in row.iter().enumerate() {
-            if !visited[row_idx][col_idx] {
-                continue;
-            }
-            let mut new_grid = grid.clone();
-            new_grid[row_idx][col_idx] = true;
-            count += is_loop(&new_grid, carot) as i32;
-        }
+    for pos in grid.positions() {
+        // The new obstruction cannot go where the guard is standing
+        if !visited[pos] || pos == carot {
+            continue;
+        }
+        let mut new_grid = grid.clone();
+        new_grid[pos] = true;
+        count += is_loop(&new_grid, carot)? as i32;
     }
-    return count;
+    return Ok(count);
 }
 
-pub fn fcn(contents: &String) {
-    let (row_len, grid, carot) = parse_contents(contents);
-    let (p1_result, visited) = part1(row_len, &grid, carot);
-    println!("Part 1: {}", p1_result);
-    let p2_result = part2(&grid, &visited, carot);
-    println!("Part 2: {}", p2_result);
-}
\ No newline at end of file
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = (Grid<bool>, (usize, usize));
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, (grid, carot): &Self::Input) -> Result<Answer> {
+        // The guard's walk, a frame for every stretch between turns
+        let mut animation = Animation::new("walk", 0);
+        let (p1_result, visited) = part1(grid, *carot, &mut animation);
+        // The guard has walked off the map by the end
+        animation.finish(&drawing(grid, &visited, None), render::map_colors)?;
+        return Ok(p1_result.into());
+    }
+
+    fn part2(&self, (grid, carot): &Self::Input) -> Result<Answer> {
+        // Part 2 only checks the spots visited in part 1
+        let (_, visited) = part1(grid, *carot, &mut Animation::none());
+        return Ok(part2(grid, &visited, *carot)?.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day6.parse(EXAMPLE).unwrap();
+        assert_eq!(Day6.part1(&input).unwrap().to_string(), "41");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day6.parse(EXAMPLE).unwrap();
+        assert_eq!(Day6.part2(&input).unwrap().to_string(), "6");
+    }
+}
@@ -1,10 +1,18 @@
-fn parse_line(line: &str) -> (usize, Vec<usize>) {
+#![allow(clippy::ptr_arg)]
+
+use crate::budget;
+use crate::error::{parse_at, Error, Result};
+use crate::solution::{Answer, Solution};
+
+fn parse_line(contents: &str, line: &str) -> Result<(usize, Vec<usize>)> {
     // Line is in the format:
     // N: M M M M ...
-    let mut colon_split = line.split(": ");
-    let n = colon_split.next().unwrap().parse::<usize>().unwrap();
-    let m = colon_split.next().unwrap().split(" ").map(|x| x.parse::<usize>().unwrap()).collect();
-    return (n, m);
+    let Some((n, m)) = line.split_once(": ") else {
+        return Err(Error::parse_at(contents, line, "Expected an equation `N: M M ...`"));
+    };
+    let n = parse_at(contents, n)?;
+    let m = m.split(" ").map(|x| parse_at(contents, x)).collect::<Result<_>>()?;
+    return Ok((n, m));
 }
 
 fn concat(first: usize, second: usize) -> usize {
@@ -12,57 +20,101 @@ fn concat(first: usize, second: usize) -> usize {
     return format!("{}{}", first, second).parse::<usize>().unwrap();
 }
 
-fn result_helper(desired: usize, check_num: usize, numbers: &[usize], use_cat: bool, _level: i32) -> bool {
+fn result_helper(desired: usize, check_num: usize, numbers: &[usize], use_cat: bool, level: i32) -> Result<bool> {
     // See if the numbers can be combined using concat, add, mul to get desired
-    // Print number of tabs according to level, then desired and numbers
-    let _level_tabs = "\t".repeat(_level as usize);
-    // println!("{}Desired: {}\n{}Numbers: {:?}", level_tabs, desired, "\t".repeat(level as usize), numbers);
-    if numbers.len() == 0 {
-        return check_num == desired;
+    // Trace with tabs according to level, then desired and numbers
+    log::trace!("{}Desired: {}, Numbers: {:?}", "\t".repeat(level as usize), desired, numbers);
+    budget::check()?;
+    if numbers.is_empty() {
+        return Ok(check_num == desired);
     }
     let add_check_num = check_num + numbers[0];
     // Check if addition works
-    if result_helper(desired, add_check_num, &numbers[1..], use_cat, _level+1) {
-        return true;
+    if result_helper(desired, add_check_num, &numbers[1..], use_cat, level+1)? {
+        return Ok(true);
     }
     let mul_check_num = if check_num == 0 { numbers[0] } else {  check_num * numbers[0] };
-    if result_helper(desired, mul_check_num, &numbers[1..], use_cat, _level+1) {
-        return true;
+    if result_helper(desired, mul_check_num, &numbers[1..], use_cat, level+1)? {
+        return Ok(true);
     }
     if use_cat {
         let cat_check_num = concat(check_num, numbers[0]);
-        if result_helper(desired, cat_check_num, &numbers[1..], use_cat, _level+1) {
-            return true;
+        if result_helper(desired, cat_check_num, &numbers[1..], use_cat, level+1)? {
+            return Ok(true);
         }
     }
-    return false;
+    return Ok(false);
 }
 
-fn result(lines: &Vec<(usize, Vec<usize>)>, use_cat: bool) -> usize {
+fn result(lines: &Vec<(usize, Vec<usize>)>, use_cat: bool) -> Result<usize> {
     let mut count = 0;
-    for (_j,(desired, numbers)) in lines.into_iter().enumerate() {
-        // println!("Checking line {}", j);
-        let result = result_helper(*desired, 0, numbers, use_cat, 0);
+    for (j, (desired, numbers)) in lines.iter().enumerate() {
+        log::trace!("Checking line {}", j);
+        let result = result_helper(*desired, 0, numbers, use_cat, 0)?;
         if result {
             count += *desired;
         }
-        // println!("Result {}: {}\n\n", j, result);
+        log::trace!("Result {}: {}", j, result);
     }
-    return count;
+    return Ok(count);
 }
 
-fn part1(lines: &Vec<(usize, Vec<usize>)>) -> usize {
+fn part1(lines: &Vec<(usize, Vec<usize>)>) -> Result<usize> {
     return result(lines, false);
 }
 
-fn part2(lines: &Vec<(usize, Vec<usize>)>) -> usize {
+fn part2(lines: &Vec<(usize, Vec<usize>)>) -> Result<usize> {
     return result(lines, true);
 }
 
-pub fn fcn(contents: &String) {
-    let lines = contents.lines().map(parse_line).collect::<Vec<(usize, Vec<usize>)>>();
-    let p1 = part1(&lines);
-    println!("Part 1 result: {}", p1);
-    let p2 = part2(&lines);
-    println!("Part 2 result: {}", p2);
-}
\ No newline at end of file
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<(usize, Vec<usize>)>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return contents.lines().map(|line| parse_line(contents, line)).collect();
+    }
+
+    fn part1(&self, lines: &Self::Input) -> Result<Answer> {
+        return Ok(part1(lines)?.into());
+    }
+
+    fn part2(&self, lines: &Self::Input) -> Result<Answer> {
+        return Ok(part2(lines)?.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day7.parse(EXAMPLE).unwrap();
+        assert_eq!(Day7.part1(&input).unwrap().to_string(), "3749");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day7.parse(EXAMPLE).unwrap();
+        assert_eq!(Day7.part2(&input).unwrap().to_string(), "11387");
+    }
+
+    #[test]
+    fn concat_numbers() {
+        assert_eq!(concat(12, 345), 12345);
+        assert_eq!(concat(15, 6), 156);
+    }
+}
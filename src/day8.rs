@@ -1,110 +1,133 @@
+#![allow(clippy::needless_range_loop, clippy::ptr_arg, clippy::type_complexity)]
+
+use crate::error::Result;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::solution::{Answer, Solution};
 use std::collections::{HashMap, HashSet};
 // For each non-period character, insert all locations into a HashMap
-// Also return the size of the matrix
-fn create_positions(contents: &String) -> (HashMap<char, Vec<(usize, usize)>>, (usize, usize)) {
+// Also return the map, which gives the bounds
+fn create_positions(contents: &str) -> Result<(HashMap<char, Vec<(usize, usize)>>, Grid<char>)> {
+    let map = Grid::parse_chars(contents)?;
     let mut positions: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
-    let mut num_rows = 0;
-    let mut num_cols = 0;
-    for (y, line) in contents.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c != '.' && c != '#' {
-                let entry = positions.entry(c).or_insert(Vec::new());
-                entry.push((x, y));
-            }
-        }
-        num_rows += 1;
-        if num_cols == 0 {
-            num_cols = line.len();
+    for (pos, &c) in map.iter() {
+        if c != '.' && c != '#' {
+            positions.entry(c).or_default().push(pos);
         }
     }
-    return (positions, (num_rows, num_cols));
+    return Ok((positions, map));
 }
 
-fn is_inbounds(pos: (isize, isize), size: (usize, usize)) -> bool {
-    return pos.0 >= 0 && pos.0 < size.1 as isize && pos.1 >= 0 && pos.1 < size.0 as isize;
-}
-
-fn insert_antinodes_part1(x0: isize, y0: isize, x1: isize, y1: isize, size: (usize, usize), antinodes: &mut HashSet<(usize, usize)>) {
-    let dx = x1 - x0;
-    let dy = y1 - y0;
-    let (p0, p1) = ((x0, y0), (x1, y1));
-    let pos = (x0 - dx, y0 - dy);
-    if is_inbounds(pos, size) && pos != p0 && pos != p1 {
-        antinodes.insert((pos.0 as usize, pos.1 as usize));
-    }
-    let pos = (x1 + dx, y1 + dy);
-    if is_inbounds(pos, size) && pos != p0 && pos != p1 {
-        antinodes.insert((pos.0 as usize, pos.1 as usize));
+fn insert_antinodes_part1(p0: Point, p1: Point, map: &Grid<char>, antinodes: &mut HashSet<Point>) {
+    let delta = p1 - p0;
+    for pos in [p0 - delta, p1 + delta] {
+        if map.contains(pos) && pos != p0 && pos != p1 {
+            antinodes.insert(pos);
+        }
     }
 }
 
-fn insert_antinodes_part2(x0: isize, y0: isize, x1: isize, y1: isize, size: (usize, usize), antinodes: &mut HashSet<(usize, usize)>) {
-    let dx = x1 - x0;
-    let dy = y1 - y0;
-    let (p0, p1) = ((x0, y0), (x1, y1));
-    let mut pos = (x0 - dx, y0 - dy);
-    while is_inbounds(pos, size) {
+fn insert_antinodes_part2(p0: Point, p1: Point, map: &Grid<char>, antinodes: &mut HashSet<Point>) {
+    let delta = p1 - p0;
+    let mut pos = p0 - delta;
+    while map.contains(pos) {
         if pos != p0 && pos != p1 {
-            antinodes.insert((pos.0 as usize, pos.1 as usize));
+            antinodes.insert(pos);
         }
-        pos = (pos.0 - dx, pos.1 - dy);
+        pos -= delta;
     }
-    pos = (x1 + dx, y1 + dy);
-    while is_inbounds(pos, size) {
+    pos = p1 + delta;
+    while map.contains(pos) {
         if pos != p0 && pos != p1 {
-            antinodes.insert((pos.0 as usize, pos.1 as usize));
+            antinodes.insert(pos);
         }
-        pos = (pos.0 + dx, pos.1 + dy);
+        pos += delta;
     }
 }
 
 fn find_all_antinodes(
     positions: &Vec<(usize, usize)>,
-    size: (usize, usize),
-    curr_antinodes: &mut HashSet<(usize, usize)>,
+    map: &Grid<char>,
+    curr_antinodes: &mut HashSet<Point>,
     is_part1: bool,
 ) {
     for j0 in 0..positions.len() {
-        let (x0, y0) = positions[j0];
-        let (x0, y0) = (x0 as isize, y0 as isize);
+        let p0 = Point::from(positions[j0]);
         for j1 in (j0+1)..positions.len() {
-            let (x1, y1) = positions[j1];
-            let (x1, y1) = (x1 as isize, y1 as isize);
+            let p1 = Point::from(positions[j1]);
             if is_part1 {
-                insert_antinodes_part1(x0, y0, x1, y1, size, curr_antinodes);
+                insert_antinodes_part1(p0, p1, map, curr_antinodes);
             } else {
-                insert_antinodes_part2(x0, y0, x1, y1, size, curr_antinodes);
+                insert_antinodes_part2(p0, p1, map, curr_antinodes);
             }
         }
     }
 }
 
-fn solve_part(positions: &HashMap<char, Vec<(usize, usize)>>, size: (usize, usize), is_part1: bool) -> usize {
-    let mut antinodes: HashSet<(usize, usize)> = HashSet::new();
-    for (_, pos) in positions {
-        find_all_antinodes(pos, size, &mut antinodes, is_part1);
+fn solve_part(positions: &HashMap<char, Vec<(usize, usize)>>, map: &Grid<char>, is_part1: bool) -> usize {
+    let mut antinodes: HashSet<Point> = HashSet::new();
+    for pos in positions.values() {
+        find_all_antinodes(pos, map, &mut antinodes, is_part1);
         if !is_part1 && pos.len() > 1 {
-            for (x, y) in pos {
-                antinodes.insert((*x, *y));
-            }
+            antinodes.extend(pos.iter().map(|&p| Point::from(p)));
         }
     }
     return antinodes.len();
 }
 
-fn part1(positions: &HashMap<char, Vec<(usize, usize)>>, size: (usize, usize)) -> usize {
-    return solve_part(positions, size, true);
+fn part1(positions: &HashMap<char, Vec<(usize, usize)>>, map: &Grid<char>) -> usize {
+    return solve_part(positions, map, true);
 }
 
-fn part2(positions: &HashMap<char, Vec<(usize, usize)>>, size: (usize, usize)) -> usize {
-    return solve_part(positions, size, false);
+fn part2(positions: &HashMap<char, Vec<(usize, usize)>>, map: &Grid<char>) -> usize {
+    return solve_part(positions, map, false);
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = (HashMap<char, Vec<(usize, usize)>>, Grid<char>);
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return create_positions(contents);
+    }
+
+    fn part1(&self, (positions, map): &Self::Input) -> Result<Answer> {
+        return Ok(part1(positions, map).into());
+    }
+
+    fn part2(&self, (positions, map): &Self::Input) -> Result<Answer> {
+        return Ok(part2(positions, map).into());
+    }
 }
 
-// Function to run for day 8
-pub fn fcn(contents: &String) {
-    let (positions, size) = create_positions(contents);
-    let p1 = part1(&positions, size);
-    println!("Part 1: {}", p1);
-    let p2 = part2(&positions, size);
-    println!("Part 2: {}", p2);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+";
+
+    #[test]
+    fn example_part1() {
+        let input = Day8.parse(EXAMPLE).unwrap();
+        assert_eq!(Day8.part1(&input).unwrap().to_string(), "14");
+    }
+
+    #[test]
+    fn example_part2() {
+        let input = Day8.parse(EXAMPLE).unwrap();
+        assert_eq!(Day8.part2(&input).unwrap().to_string(), "34");
+    }
 }
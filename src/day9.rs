@@ -1,19 +1,28 @@
-fn parse_contents(contents: &String) -> Vec<u8> {
-    return contents
+#![allow(clippy::ptr_arg, clippy::upper_case_acronyms)]
+
+use crate::error::{invalid_char, Error, Result};
+use crate::solution::{Answer, Solution};
+
+fn parse_contents(contents: &str) -> Result<Vec<u8>> {
+    let numbers = contents
         .chars()
-        .map(|x| x.to_digit(10).unwrap() as u8)
-        .collect::<Vec<u8>>();
+        .enumerate()
+        .map(|(idx, x)| match x.to_digit(10) {
+            Some(d) => Ok(d as u8),
+            None => Err(invalid_char(contents, contents, idx, x, "digit")),
+        })
+        .collect::<Result<Vec<u8>>>()?;
+    // Make sure the total memory can fit into a u32
+    let total_memory = numbers.iter().fold(0usize, |acc, x| acc + *x as usize);
+    if total_memory > u32::MAX as usize {
+        return Err(Error::invalid(format!("Total memory needed {} exceeds u32::MAX {}", total_memory, u32::MAX)));
+    }
+    return Ok(numbers);
 }
 
 fn create_memory(numbers: &Vec<u8>) -> Vec<u32> {
-    let total_memory = numbers.into_iter().fold(0usize, |acc, x| acc + *x as usize);
-    // Assert total memory can fit into a u32
-    assert!(
-        total_memory <= u32::MAX as usize,
-        "Total memory needed {} exceeds u32::MAX {}",
-        total_memory,
-        u32::MAX
-    );
+    let total_memory = numbers.iter().fold(0usize, |acc, x| acc + *x as usize);
+    // Total memory is checked to fit into a u32 when parsing
     let mut alloc_vec = Vec::<u32>::with_capacity(total_memory);
     let mut id = 0;
     for (idx, num) in numbers.iter().enumerate() {
@@ -119,13 +128,15 @@ impl DLL {
         return self.data.len() - 1;
     }
 
-    fn print(&self) {
+    fn nodes_string(&self) -> String {
+        let mut lines = Vec::new();
         let mut idx = self.head_idx;
         while idx != usize::MAX {
             let node = &self.data[idx];
-            println!("Node: id: {}, size: {}", node.id, node.size);
+            lines.push(format!("Node: id: {}, size: {}", node.id, node.size));
             idx = node.next_idx;
         }
+        return lines.join("\n");
     }
     fn swap_into(&mut self, node_curr_idx: usize, node_dest_idx: usize) {
         let curr_next = self.data[node_curr_idx].next_idx;
@@ -173,7 +184,9 @@ fn part2(numbers: &Vec<u8>) -> usize {
             memory.push_back(u32::MAX, *num);
         }
     }
-    memory.print();
+    if log::log_enabled!(log::Level::Trace) {
+        log::trace!("Memory:\n{}", memory.nodes_string());
+    }
     // Defrag memory using _whole blocks_
     // Requires swaps from beginning to end
     // Also defrag memory on the end of the list on the fly (consolidate free memory)
@@ -186,7 +199,7 @@ fn part2(numbers: &Vec<u8>) -> usize {
         if node.id == u32::MAX {
             // Find a node with id != u32::MAX
             while rev_node_idx != usize::MAX && (memory.data[rev_node_idx].id == u32::MAX || memory.data[rev_node_idx].size < node.size) {
-                println!("rev_node_idx: {}", rev_node_idx);
+                log::trace!("rev_node_idx: {}", rev_node_idx);
                 rev_node_idx = memory.data[rev_node_idx].prev_idx;
             }
             if rev_node_idx == usize::MAX {
@@ -199,11 +212,55 @@ fn part2(numbers: &Vec<u8>) -> usize {
     return 0;
 }
 
-pub fn fcn(contents: &String) {
-    // First convert every character to a number
-    let numbers = parse_contents(contents);
-    let p1 = part1(&numbers);
-    println!("Part 1: {}", p1);
-    let p2 = part2(&numbers);
-    println!("Part 2: {}", p2);
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<u8>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        // First convert every character to a number
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, numbers: &Self::Input) -> Result<Answer> {
+        return Ok(part1(numbers).into());
+    }
+
+    fn part2(&self, numbers: &Self::Input) -> Result<Answer> {
+        return Ok(part2(numbers).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2333133121414131402";
+
+    #[test]
+    fn example_part1() {
+        let input = Day9.parse(EXAMPLE).unwrap();
+        assert_eq!(Day9.part1(&input).unwrap().to_string(), "1928");
+    }
+
+    #[test]
+    fn example_with_trailing_newline() {
+        let input = Day9.parse(&crate::input::normalize("2333133121414131402\r\n")).unwrap();
+        assert_eq!(Day9.part1(&input).unwrap().to_string(), "1928");
+    }
+
+    #[test]
+    fn defrag_small_disk() {
+        let mut memory = create_memory(&parse_contents("12345").unwrap());
+        let free = u32::MAX;
+        assert_eq!(memory, vec![0, free, free, 1, 1, 1, free, free, free, free, 2, 2, 2, 2, 2]);
+        defrag_memory(&mut memory);
+        assert_eq!(memory, vec![0, 2, 2, 1, 1, 1, 2, 2, 2, free, free, free, free, free, free]);
+    }
+
+    #[test]
+    fn invalid_digit() {
+        let err = Day9.parse("123x5").unwrap_err();
+        assert_eq!(err.to_string(), "Parse error at line 1, column 4: Invalid digit `x`");
+    }
 }
@@ -0,0 +1,148 @@
+use crate::timing::format_duration;
+use std::fmt;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Errors from loading an input, parsing it or solving a puzzle
+#[derive(Debug)]
+pub enum Error {
+    // Reading or writing an input or answers file failed
+    Io { path: PathBuf, source: io::Error },
+    // The input is malformed, with the 1-based line and column where the problem is
+    Parse { line: usize, column: usize, msg: String },
+    // The input parsed but describes a puzzle that cannot be solved
+    InvalidPuzzle(String),
+    // A command could not be carried out, e.g. generating a day that already exists
+    Command(String),
+    // A part used up its time limit, see `budget`
+    Timeout(Duration),
+    // Puzzle parameters could not be read, see `params`
+    Params(String),
+    // Parsing or solving panicked, with the panic message
+    Panic(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Error {
+        return Error::Io { path: path.into(), source };
+    }
+
+    /// Parse error located at the start of `part`, which must be a slice of `contents`
+    pub fn parse_at(contents: &str, part: &str, msg: impl Into<String>) -> Error {
+        let (line, column) = position(contents, part);
+        return Error::Parse { line, column, msg: msg.into() };
+    }
+
+    pub fn invalid(msg: impl Into<String>) -> Error {
+        return Error::InvalidPuzzle(msg.into());
+    }
+
+    pub fn command(msg: impl Into<String>) -> Error {
+        return Error::Command(msg.into());
+    }
+
+    pub fn params(msg: impl Into<String>) -> Error {
+        return Error::Params(msg.into());
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Parse { line, column, msg } => write!(f, "Parse error at line {}, column {}: {}", line, column, msg),
+            Error::InvalidPuzzle(msg) => write!(f, "Invalid puzzle: {}", msg),
+            Error::Command(msg) => write!(f, "{}", msg),
+            Error::Timeout(limit) => write!(f, "TIMEOUT after {}", format_duration(*limit)),
+            Error::Params(msg) => write!(f, "Invalid parameters: {}", msg),
+            Error::Panic(msg) => write!(f, "panicked: {}", msg),
+        };
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        };
+    }
+}
+
+// Find the 1-based (line, column) where `part` starts within `contents`.
+// Slices that are not part of `contents` are reported at the start of the input.
+fn position(contents: &str, part: &str) -> (usize, usize) {
+    let start = contents.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).wrapping_sub(start);
+    if offset > contents.len() || offset + part.len() > contents.len() {
+        return (1, 1);
+    }
+    let before = &contents[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    return (line, before[line_start..].chars().count() + 1);
+}
+
+/// Parse `s`, a slice of `contents`, as a `T`, reporting where it is in `contents` on failure
+pub fn parse_at<T: FromStr>(contents: &str, s: &str) -> Result<T> {
+    return s.parse().map_err(|_| {
+        Error::parse_at(contents, s, format!("`{}` is not a valid {}", s, std::any::type_name::<T>()))
+    });
+}
+
+/// Parse error for an unexpected character `c` found at `idx` chars into `s`, a slice of `contents`
+pub fn invalid_char(contents: &str, s: &str, idx: usize, c: char, what: &str) -> Error {
+    let offset = s.char_indices().nth(idx).map(|(i, _)| i).unwrap_or(s.len());
+    return Error::parse_at(contents, &s[offset..], format!("Invalid {} `{}`", what, c));
+}
+
+/// Run `f`, turning a panic inside it into `Error::Panic` so that one broken day does not stop the others
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    return panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let msg = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+            (Some(msg), _) => msg.to_string(),
+            (_, Some(msg)) => msg.clone(),
+            _ => "unknown panic".to_string(),
+        };
+        return Err(Error::Panic(msg));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_of_slices() {
+        let contents = "abc\ndef\n\nghi";
+        assert_eq!(position(contents, &contents[0..]), (1, 1));
+        assert_eq!(position(contents, &contents[5..]), (2, 2));
+        assert_eq!(position(contents, &contents[9..]), (4, 1));
+        assert_eq!(position(contents, "elsewhere"), (1, 1));
+    }
+
+    #[test]
+    fn parse_error_message() {
+        let contents = "1 2\n3 x4";
+        let field = contents.lines().nth(1).unwrap().split(' ').nth(1).unwrap();
+        let err = parse_at::<u32>(contents, field).unwrap_err();
+        assert_eq!(err.to_string(), "Parse error at line 2, column 3: `x4` is not a valid u32");
+        let err = invalid_char(contents, field, 1, '4', "letter");
+        assert_eq!(err.to_string(), "Parse error at line 2, column 4: Invalid letter `4`");
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        let v: Vec<u8> = vec![];
+        let err = catch_panic(|| Ok(v[3])).unwrap_err();
+        assert!(matches!(&err, Error::Panic(msg) if msg.contains("index out of bounds")));
+        assert_eq!(catch_panic(|| Err::<(), _>(Error::command("boom"))).unwrap_err().to_string(), "boom");
+        assert_eq!(catch_panic(|| -> Result<u8> { panic!("boom") }).unwrap_err().to_string(), "panicked: boom");
+        assert_eq!(catch_panic(|| Ok(1)).unwrap(), 1);
+    }
+}
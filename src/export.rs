@@ -0,0 +1,207 @@
+use crate::error::{Error, Result};
+use crate::timing::DayTimings;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// File formats the results can be exported as
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Format> {
+        return match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        };
+    }
+}
+
+/// What happened to one part of a day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartStatus {
+    // The answer matched the stored one
+    Pass,
+    // The answer did not match the stored one
+    Fail,
+    // There was no stored answer to check against
+    New,
+    // The answer was not checked, as the run was not verifying
+    Unchecked,
+    // The part has not been solved yet
+    Unsolved,
+    // The part returned an error
+    Error,
+    // The part used up its time limit
+    Timeout,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PartRecord {
+    pub part: u8,
+    pub answer: Option<String>,
+    pub expected: Option<String>,
+    pub status: PartStatus,
+    pub time_ns: u64,
+    // The answer and time are from an earlier run, see `cache`
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+/// Result of running a day on one input. If the day could not be run, it has an error and no timings.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DayRecord {
+    pub day: usize,
+    pub input: Option<String>,
+    pub load_ns: Option<u64>,
+    pub parse_ns: Option<u64>,
+    pub error: Option<String>,
+    pub parts: Vec<PartRecord>,
+}
+
+pub fn nanos(d: Duration) -> u64 {
+    return d.as_nanos() as u64;
+}
+
+impl DayRecord {
+    pub fn new(day: usize, input: Option<String>, timings: Option<&DayTimings>, parts: Vec<PartRecord>, error: Option<String>) -> DayRecord {
+        return DayRecord {
+            day,
+            input,
+            load_ns: timings.map(|t| nanos(t.load)),
+            parse_ns: timings.map(|t| nanos(t.parse)),
+            error,
+            parts,
+        };
+    }
+}
+
+// One row of the CSV export, which has a row per part, or a single row for a day that could not be run
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    day: usize,
+    input: Option<&'a str>,
+    part: Option<u8>,
+    answer: Option<&'a str>,
+    expected: Option<&'a str>,
+    status: PartStatus,
+    time_ns: Option<u64>,
+    cached: Option<bool>,
+    load_ns: Option<u64>,
+    parse_ns: Option<u64>,
+    error: Option<&'a str>,
+}
+
+impl<'a> CsvRow<'a> {
+    fn new(day: &'a DayRecord, part: Option<&'a PartRecord>) -> CsvRow<'a> {
+        return CsvRow {
+            day: day.day,
+            input: day.input.as_deref(),
+            part: part.map(|p| p.part),
+            answer: part.and_then(|p| p.answer.as_deref()),
+            expected: part.and_then(|p| p.expected.as_deref()),
+            status: part.map(|p| p.status).unwrap_or(PartStatus::Error),
+            time_ns: part.map(|p| p.time_ns),
+            cached: part.map(|p| p.cached),
+            load_ns: day.load_ns,
+            parse_ns: day.parse_ns,
+            error: part.map_or(day.error.as_deref(), |p| p.error.as_deref()),
+        };
+    }
+}
+
+fn write_csv(writer: impl Write, records: &[DayRecord]) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for day in records {
+        if day.parts.is_empty() {
+            writer.serialize(CsvRow::new(day, None))?;
+        }
+        for part in &day.parts {
+            writer.serialize(CsvRow::new(day, Some(part)))?;
+        }
+    }
+    return writer.flush();
+}
+
+/// Write the records to `path` in the given format
+pub fn write(path: &Path, format: Format, records: &[DayRecord]) -> Result<()> {
+    let file = File::create(path).map_err(|e| Error::io(path, e))?;
+    let mut writer = BufWriter::new(file);
+    let written = match format {
+        Format::Json => serde_json::to_writer_pretty(&mut writer, records).map_err(io::Error::from),
+        Format::Csv => write_csv(&mut writer, records),
+    };
+    return written.and_then(|_| writer.flush()).map_err(|e| Error::io(path, e));
+}
+
+/// Read the records back from a JSON export
+pub fn read_json(path: &Path) -> Result<Vec<DayRecord>> {
+    let file = File::open(path).map_err(|e| Error::io(path, e))?;
+    return serde_json::from_reader(io::BufReader::new(file)).map_err(|e| Error::io(path, e.into()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<DayRecord> {
+        let part = |part, answer: &str, status| PartRecord {
+            part,
+            answer: Some(answer.to_string()),
+            expected: None,
+            status,
+            time_ns: 5,
+            cached: part == 2,
+            error: None,
+        };
+        return vec![
+            DayRecord {
+                day: 1,
+                input: None,
+                load_ns: Some(1),
+                parse_ns: Some(2),
+                error: None,
+                parts: vec![part(1, "11", PartStatus::Pass), part(2, "3,1", PartStatus::New)],
+            },
+            DayRecord::new(2, Some("alice".to_string()), None, vec![], Some("missing".to_string())),
+        ];
+    }
+
+    #[test]
+    fn csv_has_a_row_per_part() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &records()).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+day,input,part,answer,expected,status,time_ns,cached,load_ns,parse_ns,error
+1,,1,11,,pass,5,false,1,2,
+1,,2,\"3,1\",,new,5,true,1,2,
+2,alice,,,,error,,,,,missing
+");
+    }
+
+    #[test]
+    fn json_keeps_parts_nested() {
+        let json: serde_json::Value = serde_json::to_value(records()).unwrap();
+        assert_eq!(json[0]["parts"][1]["answer"], "3,1");
+        assert_eq!(json[0]["parts"][0]["status"], "pass");
+        assert_eq!(json[0]["parts"][1]["cached"], true);
+        assert_eq!(json[1]["error"], "missing");
+        assert!(json[1]["load_ns"].is_null());
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(Format::from_path(Path::new("out/results.JSON")), Some(Format::Json));
+        assert_eq!(Format::from_path(Path::new("results.csv")), Some(Format::Csv));
+        assert_eq!(Format::from_path(Path::new("results")), None);
+    }
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Position or offset on a map, with row 0 at the top and column 0 on the left.
+/// Unlike grid positions it can be negative, e.g. a step off the map or the offset between two antennas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Point {
+    pub const ZERO: Point = Point::new(0, 0);
+
+    pub const fn new(row: isize, col: isize) -> Point {
+        return Point { row, col };
+    }
+
+    /// Grid position of the point, if neither coordinate is negative
+    pub fn to_pos(self) -> Option<(usize, usize)> {
+        return Some((usize::try_from(self.row).ok()?, usize::try_from(self.col).ok()?));
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        return self.row.abs_diff(other.row) + self.col.abs_diff(other.col);
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Point {
+        return Point::new(row as isize, col as isize);
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((row, col): (isize, isize)) -> Point {
+        return Point::new(row, col);
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        return Point::new(self.row + other.row, self.col + other.col);
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        return Point::new(self.row - other.row, self.col - other.col);
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        return Point::new(-self.row, -self.col);
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, scale: isize) -> Point {
+        return Point::new(self.row * scale, self.col * scale);
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "({}, {})", self.row, self.col);
+    }
+}
+
+/// One of the 4 orthogonal directions on a map, where north is up
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// Offset of one step in this direction
+    pub fn offset(self) -> Point {
+        return match self {
+            Direction::North => Point::new(-1, 0),
+            Direction::East => Point::new(0, 1),
+            Direction::South => Point::new(1, 0),
+            Direction::West => Point::new(0, -1),
+        };
+    }
+
+    /// Direction after turning 90 degrees counterclockwise
+    pub fn turn_left(self) -> Direction {
+        return match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        };
+    }
+
+    /// Direction after turning 90 degrees clockwise
+    pub fn turn_right(self) -> Direction {
+        return self.turn_left().opposite();
+    }
+
+    pub fn opposite(self) -> Direction {
+        return self.turn_left().turn_left();
+    }
+
+    /// Direction of an arrow, one of `^v<>`
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        return match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        };
+    }
+
+    /// Direction of a compass letter, one of `NSEW`
+    pub fn from_letter(c: char) -> Option<Direction> {
+        return match c {
+            'N' => Some(Direction::North),
+            'E' => Some(Direction::East),
+            'S' => Some(Direction::South),
+            'W' => Some(Direction::West),
+            _ => None,
+        };
+    }
+
+    /// Arrow pointing in this direction, the reverse of `from_arrow`
+    pub fn arrow(self) -> char {
+        return match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        };
+    }
+}
+
+/// One of the 8 directions to the neighbours of a cell, including the diagonal ones
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from north
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    pub fn offset(self) -> Point {
+        return match self {
+            Direction8::North => Point::new(-1, 0),
+            Direction8::NorthEast => Point::new(-1, 1),
+            Direction8::East => Point::new(0, 1),
+            Direction8::SouthEast => Point::new(1, 1),
+            Direction8::South => Point::new(1, 0),
+            Direction8::SouthWest => Point::new(1, -1),
+            Direction8::West => Point::new(0, -1),
+            Direction8::NorthWest => Point::new(-1, -1),
+        };
+    }
+
+    // Position in `ALL`, so that turning is a step along it
+    fn index(self) -> usize {
+        return Direction8::ALL.iter().position(|&dir| dir == self).unwrap();
+    }
+
+    /// Direction after turning 45 degrees counterclockwise
+    pub fn turn_left(self) -> Direction8 {
+        return Direction8::ALL[(self.index() + 7) % 8];
+    }
+
+    /// Direction after turning 45 degrees clockwise
+    pub fn turn_right(self) -> Direction8 {
+        return Direction8::ALL[(self.index() + 1) % 8];
+    }
+
+    pub fn opposite(self) -> Direction8 {
+        return Direction8::ALL[(self.index() + 4) % 8];
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        return !self.index().is_multiple_of(2);
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Direction8 {
+        return match dir {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning_and_opposites() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.opposite());
+            assert_eq!(dir.offset() + dir.opposite().offset(), Point::ZERO);
+            assert_eq!(Direction8::from(dir).turn_right().turn_right(), Direction8::from(dir.turn_right()));
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction8::NorthWest.turn_right(), Direction8::North);
+        assert_eq!(Direction8::SouthEast.opposite(), Direction8::NorthWest);
+        assert_eq!(Direction8::ALL.iter().filter(|dir| dir.is_diagonal()).count(), 4);
+    }
+
+    #[test]
+    fn parse_arrows_and_letters() {
+        let arrows: Vec<_> = "^>v<".chars().map(|c| Direction::from_arrow(c).unwrap()).collect();
+        assert_eq!(arrows, Direction::ALL);
+        assert_eq!("NESW".chars().map(Direction::from_letter).collect::<Option<Vec<_>>>(), Some(arrows));
+        assert_eq!(Direction::from_arrow('x'), None);
+        assert_eq!(Direction::ALL.map(Direction::arrow), ['^', '>', 'v', '<']);
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let p = Point::from((2usize, 3usize));
+        assert_eq!(p + Direction::West.offset() * 3, Point::new(2, 0));
+        assert_eq!((p - Point::new(3, 0)).to_pos(), None);
+        assert_eq!((-p + p * 2).to_pos(), Some((2, 3)));
+        assert_eq!(p.manhattan(Point::new(-1, 4)), 4);
+    }
+}
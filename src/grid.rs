@@ -0,0 +1,209 @@
+use crate::error::{invalid_char, Error, Result};
+use crate::geometry::{Direction, Direction8, Point};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// Rectangular map of cells stored row after row, e.g. the character maps most days are given.
+/// Cells are indexed by (row, col), with row 0 at the top.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid with every cell set to `value`
+    pub fn new(rows: usize, cols: usize, value: T) -> Grid<T> where T: Clone {
+        return Grid { rows, cols, cells: vec![value; rows * cols] };
+    }
+
+    /// Grid whose cells are `f` of their position
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut((usize, usize)) -> T) -> Grid<T> {
+        let cells = (0..rows * cols).map(|i| f((i / cols, i % cols))).collect();
+        return Grid { rows, cols, cells };
+    }
+
+    /// Parse a map with one line per row, turning each character into a cell with `f`. Characters that `f`
+    /// does not accept are reported as an invalid `what`, and every line has to be as long as the first.
+    pub fn parse(contents: &str, what: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Grid<T>> {
+        let mut cells = Vec::with_capacity(contents.len());
+        let mut rows = 0;
+        let mut cols = 0;
+        for line in contents.lines() {
+            let start = cells.len();
+            for (idx, c) in line.chars().enumerate() {
+                let Some(cell) = f(c) else {
+                    return Err(invalid_char(contents, line, idx, c, what));
+                };
+                cells.push(cell);
+            }
+            let len = cells.len() - start;
+            if rows == 0 {
+                cols = len;
+            } else if len != cols {
+                return Err(Error::parse_at(contents, line, format!("Expected a row of {} cells, got {}", cols, len)));
+            }
+            rows += 1;
+        }
+        if cells.is_empty() {
+            return Err(Error::invalid("The map is empty"));
+        }
+        return Ok(Grid { rows, cols, cells });
+    }
+
+    pub fn rows(&self) -> usize {
+        return self.rows;
+    }
+
+    pub fn cols(&self) -> usize {
+        return self.cols;
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        return &self.cells[row * self.cols..(row + 1) * self.cols];
+    }
+
+    /// Whether a position, which may be off the grid on any side, is on the grid
+    pub fn contains(&self, pos: Point) -> bool {
+        return pos.to_pos().is_some_and(|(row, col)| row < self.rows && col < self.cols);
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        return Some(&self.cells[row * self.cols + col]);
+    }
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        return Some(&mut self.cells[row * self.cols + col]);
+    }
+
+    /// Position `offset` away from `pos`, if it is on the grid
+    pub fn step(&self, pos: (usize, usize), offset: Point) -> Option<(usize, usize)> {
+        let next = Point::from(pos) + offset;
+        return if self.contains(next) { next.to_pos() } else { None };
+    }
+
+    /// The orthogonal neighbours of `pos` that are on the grid, clockwise from the one above
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        return Direction::ALL.iter().filter_map(move |dir| self.step(pos, dir.offset()));
+    }
+
+    /// All neighbours of `pos`, including diagonal ones, that are on the grid, clockwise from the one above
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        return Direction8::ALL.iter().filter_map(move |dir| self.step(pos, dir.offset()));
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let cols = self.cols;
+        return (0..self.cells.len()).map(move |i| (i / cols, i % cols));
+    }
+
+    /// Every position with its cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        return self.positions().zip(self.cells.iter());
+    }
+
+    /// First position, row by row, whose cell matches `predicate`
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        return self.cells.iter().position(predicate).map(|i| (i / self.cols, i % self.cols));
+    }
+
+    /// First position, row by row, holding `value`
+    pub fn find(&self, value: &T) -> Option<(usize, usize)> where T: PartialEq {
+        return self.position(|cell| cell == value);
+    }
+
+    /// Grid of the same size with `f` applied to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        return Grid { rows: self.rows, cols: self.cols, cells: self.cells.iter().map(f).collect() };
+    }
+}
+
+impl Grid<char> {
+    /// Parse a map of characters, which only has to be rectangular
+    pub fn parse_chars(contents: &str) -> Result<Grid<char>> {
+        return Grid::parse(contents, "character", Some);
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is outside of the {}x{} grid", row, col, self.rows, self.cols);
+        return &self.cells[row * self.cols + col];
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is outside of the {}x{} grid", row, col, self.rows, self.cols);
+        return &mut self.cells[row * self.cols + col];
+    }
+}
+
+// Draws one line per row, without a newline at the end
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(row) {
+                write!(f, "{}", cell)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let grid = Grid::parse("1.2\n345", "height", |c| c.to_digit(10).or(if c == '.' { Some(0) } else { None })).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[(1, 2)], 5);
+        assert_eq!(grid.to_string(), "102\n345");
+        assert_eq!(grid.find(&4), Some((1, 1)));
+
+        let err = Grid::parse("12\n3x", "height", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "Parse error at line 2, column 2: Invalid height `x`");
+        let err = Grid::parse_chars("ab\nabc").unwrap_err();
+        assert_eq!(err.to_string(), "Parse error at line 2, column 1: Expected a row of 2 cells, got 3");
+        assert!(Grid::parse_chars("").is_err());
+    }
+
+    #[test]
+    fn neighbours_stay_on_the_grid() {
+        let grid = Grid::new(2, 3, '.');
+        assert_eq!(grid.neighbors4((0, 0)).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors4((1, 1)).collect::<Vec<_>>(), vec![(0, 1), (1, 2), (1, 0)]);
+        assert_eq!(grid.neighbors8((0, 2)).collect::<Vec<_>>(), vec![(1, 2), (1, 1), (0, 1)]);
+        assert_eq!(grid.step((1, 2), Direction::East.offset()), None);
+        assert!(!grid.contains(Point::new(-1, 0)) && grid.contains(Point::new(1, 2)) && !grid.contains(Point::new(2, 0)));
+        assert_eq!(grid.get((2, 0)), None);
+    }
+
+    #[test]
+    fn positions_are_row_major() {
+        let grid = Grid::from_fn(2, 2, |(row, col)| row * 10 + col);
+        assert_eq!(grid.iter().map(|(pos, &cell)| (pos, cell)).collect::<Vec<_>>(), vec![
+            ((0, 0), 0),
+            ((0, 1), 1),
+            ((1, 0), 10),
+            ((1, 1), 11),
+        ]);
+        assert_eq!(grid.map(|cell| cell % 2 == 1).position(|&odd| odd), Some((0, 1)));
+        assert_eq!(grid.row(1), &[10, 11]);
+    }
+}
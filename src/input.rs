@@ -0,0 +1,130 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Where a puzzle input is read from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+/// One input to run a day against, labelled unless it is the day's usual `data/dayX.txt`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Input {
+    pub source: Source,
+    pub label: Option<String>,
+}
+
+impl Input {
+    /// Read the input, normalized so that every day sees the same framing however the file was saved
+    pub fn read(&self) -> Result<String> {
+        let contents = match &self.source {
+            Source::File(path) => fs::read_to_string(path).map_err(|e| Error::io(path, e))?,
+            Source::Stdin => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents).map_err(|e| Error::io("<stdin>", e))?;
+                contents
+            }
+        };
+        return Ok(normalize(&contents));
+    }
+
+    /// Path of the input file, which stdin does not have
+    pub fn path(&self) -> Option<&Path> {
+        return match &self.source {
+            Source::File(path) => Some(path),
+            Source::Stdin => None,
+        };
+    }
+}
+
+/// Normalize the framing of an input: CRLF line endings become LF, and a byte order mark, whitespace at
+/// the end of each line and blank lines at the end are dropped, along with the final newline. Days can then
+/// split on `\n\n` and parse the last line as it is.
+pub fn normalize(contents: &str) -> String {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    let lines = contents.lines().map(|line| line.trim_end()).collect::<Vec<_>>();
+    return lines.join("\n").trim_end_matches('\n').to_string();
+}
+
+// Path of the input file for a given day
+pub fn input_path(data_dir: &Path, day: usize) -> PathBuf {
+    return data_dir.join(format!("day{}.txt", day));
+}
+
+/// Input given on the command line, where `-` means stdin
+pub fn explicit_input(arg: &Path) -> Input {
+    if arg == Path::new("-") {
+        return Input { source: Source::Stdin, label: Some("stdin".to_string()) };
+    }
+    return Input { source: Source::File(arg.to_path_buf()), label: Some(arg.display().to_string()) };
+}
+
+/// Inputs for a day: `dayX.txt` followed by every `dayX/*.txt` in name order.
+/// If there are neither, `dayX.txt` is still returned so that reading it reports it missing.
+pub fn day_inputs(data_dir: &Path, day: usize) -> Result<Vec<Input>> {
+    let mut inputs = Vec::new();
+    let default_path = input_path(data_dir, day);
+    if default_path.exists() {
+        inputs.push(Input { source: Source::File(default_path.clone()), label: None });
+    }
+    let day_dir = data_dir.join(format!("day{}", day));
+    if day_dir.is_dir() {
+        let entries = fs::read_dir(&day_dir).map_err(|e| Error::io(&day_dir, e))?;
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| Error::io(&day_dir, e))?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        for path in paths {
+            let label = path.file_stem().unwrap().to_string_lossy().to_string();
+            inputs.push(Input { source: Source::File(path), label: Some(label) });
+        }
+    }
+    if inputs.is_empty() {
+        inputs.push(Input { source: Source::File(default_path), label: None });
+    }
+    return Ok(inputs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_from_day_directory() {
+        let data_dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(data_dir.join("day3")).unwrap();
+        fs::write(data_dir.join("day3.txt"), "").unwrap();
+        fs::write(data_dir.join("day3/bob.txt"), "").unwrap();
+        fs::write(data_dir.join("day3/alice.txt"), "").unwrap();
+        fs::write(data_dir.join("day3/alice.answers"), "").unwrap();
+
+        let labels = |day| day_inputs(&data_dir, day).unwrap().into_iter().map(|i| i.label).collect::<Vec<_>>();
+        assert_eq!(labels(3), vec![None, Some("alice".to_string()), Some("bob".to_string())]);
+        // A day with no inputs at all still gets its default file, which then fails to load
+        assert_eq!(day_inputs(&data_dir, 4).unwrap(), vec![Input { source: Source::File(data_dir.join("day4.txt")), label: None }]);
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn normalized_framing() {
+        assert_eq!(normalize("1|2\r\n3|4  \r\n\r\n5,6\r\n\r\n"), "1|2\n3|4\n\n5,6");
+        assert_eq!(normalize("\u{feff}2333133121414131402\n"), "2333133121414131402");
+        assert_eq!(normalize("125 17 \n"), "125 17");
+        assert_eq!(normalize("  #.\n"), "  #.");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn stdin_input() {
+        let input = explicit_input(Path::new("-"));
+        assert_eq!(input.source, Source::Stdin);
+        assert_eq!(input.path(), None);
+    }
+}
@@ -0,0 +1,46 @@
+// The codebase deliberately uses explicit returns
+#![allow(clippy::needless_return)]
+
+pub mod answers;
+pub mod budget;
+pub mod cache;
+pub mod compare;
+pub mod error;
+pub mod export;
+pub mod fnv;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod memory;
+pub mod params;
+pub mod render;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod timing;
+pub mod trace;
+pub mod watch;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20; // TODO: Implement day 20
+pub mod day21;
+// Alternative attempt at day 21, only run when chosen with `--impl v2`
+pub mod day21_2;
@@ -1,68 +1,265 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20; // TODO: Implement day 20
-mod day21;
-use std::fs;
+// The codebase deliberately uses explicit returns
+#![allow(clippy::needless_return)]
 
-use itertools::enumerate;
+mod cli;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
 
-// Function to read the input file for a given day
-fn setup(day: usize) -> String {
-    let filename = format!("data/day{}.txt", day);
-    let contents = fs::read_to_string(filename).expect("Could not read file");
-    return contents;
+use advent_of_code::cache::{AnswerCache, CACHE_FILE};
+use advent_of_code::compare;
+use advent_of_code::export;
+use advent_of_code::memory;
+use advent_of_code::params::PARAMS_FILE;
+use advent_of_code::runner::{self, RunOptions, DAYS};
+use advent_of_code::scaffold;
+use advent_of_code::timing::{self, time};
+use advent_of_code::trace;
+use advent_of_code::watch::{self, Watcher};
+use clap::Parser;
+use cli::{Cli, Command, CompareArgs, NewArgs, RunArgs, WatchArgs};
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+// Directory holding the inputs, `data/dayX.txt` and `data/dayX/*.txt`
+const DATA_DIR: &str = "data";
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let log_days = match cli.log_days.as_ref().map(|days| days.resolve(DAYS.len())).transpose() {
+        Ok(days) => days,
+        Err(msg) => {
+            eprintln!("error: --log-days: {}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    // Selecting days to log for is only useful with some logging
+    let verbose = if log_days.is_some() { cli.verbose.max(1) } else { cli.verbose };
+    trace::init(trace::verbosity_level(verbose), log_days);
+
+    return match cli.command {
+        Command::Run(args) => run(args),
+        Command::New(args) => new(args),
+        Command::Compare(args) => compare(args),
+        Command::Watch(args) => watch(args),
+    };
+}
+
+fn watch(args: WatchArgs) -> ExitCode {
+    let day = args.day as usize;
+    if day > DAYS.len() {
+        eprintln!("error: Day {} is not implemented (days 1-{} are available)", day, DAYS.len());
+        return ExitCode::FAILURE;
+    }
+    // Check the parameters now rather than on every run
+    if let Err(e) = args.params.load() {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    let mut paths = match watch::day_paths(Path::new("src"), Path::new(DATA_DIR), day) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    paths.push(args.params.params.clone().unwrap_or(PARAMS_FILE.into()));
+    let export = watch::export_path(Path::new("target"), day);
+    let mut watcher = Watcher::new(paths.clone());
+    let names = paths.iter().map(|path| path.display().to_string()).collect::<Vec<_>>();
+    println!("Watching {} for changes, press Ctrl-C to stop", names.join(", "));
+
+    let mut previous: Option<Vec<_>> = None;
+    loop {
+        println!("\nTesting day {}:\n", day);
+        match watch::run_tests(day) {
+            Ok(true) => println!("\nTests passed"),
+            Ok(false) => println!("\nTests failed"),
+            Err(e) => eprintln!("error: {}", e),
+        }
+        match watch::run_day(day, &args.run_args(), &export) {
+            Ok(records) => {
+                if let Some(previous) = &previous {
+                    println!("\nChanges since the last run:");
+                    for line in watch::changes(previous, &records) {
+                        println!("{}", line);
+                    }
+                }
+                previous = Some(records);
+            }
+            Err(e) => eprintln!("error: {}", e),
+        }
+        watcher.wait(watch::POLL_INTERVAL);
+        println!("\n{}", "=".repeat(70));
+    }
+}
+
+fn compare(args: CompareArgs) -> ExitCode {
+    let days = match args.days.resolve(DAYS.len()) {
+        Ok(days) => days,
+        Err(msg) => {
+            eprintln!("error: {}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.input.is_some() && days.len() != 1 {
+        eprintln!("error: --input can only be used when comparing a single day");
+        return ExitCode::FAILURE;
+    }
+    let params = match args.params.load() {
+        Ok(params) => params,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    // Comparing every day only covers those that have other implementations
+    let days = if days.len() > 1 {
+        days.into_iter().filter(|&day| runner::implementations(day).len() > 1).collect()
+    } else {
+        days
+    };
+
+    // Run one day at a time so the timings can be compared
+    let mut num_different = 0;
+    let mut failed_days = Vec::new();
+    for (day, input) in runner::tasks(&days, Path::new(DATA_DIR), args.input.as_deref()) {
+        let label = input.as_ref().ok().and_then(|input| input.label.clone());
+        println!("\nDay {} implementations:\n", timing::day_name(day, &label));
+        match input.and_then(|input| input.read()) {
+            Ok(contents) => {
+                let mut out = String::new();
+                num_different += compare::compare_day(day, &contents, &params.day(day), cli::parts(args.part), cli::time_limit(args.time_limit), &mut out);
+                print!("{}", out);
+            }
+            Err(e) => {
+                println!("Failed: {}", e);
+                failed_days.push(timing::day_name(day, &label));
+            }
+        }
+    }
+    if !failed_days.is_empty() {
+        eprintln!("\nDays that could not be compared: {}", failed_days.join(", "));
+    }
+    if num_different > 0 {
+        eprintln!("\n{} implementation(s) disagree with the main one", num_different);
+    }
+    if !failed_days.is_empty() || num_different > 0 {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
 }
 
-fn main() {
-    // List of days to run as a tuple of the function and a boolean to evaluate it
-    let days: Vec<(&dyn Fn(&String) -> (), bool)> = vec![
-        (&day1::fcn, false),
-        (&day2::fcn, false),
-        (&day3::fcn, false),
-        (&day4::fcn, false),
-        (&day5::fcn, false),
-        (&day6::fcn, false),
-        (&day7::fcn, false),
-        (&day8::fcn, false),
-        (&day9::fcn, false),
-        (&day10::fcn, false),
-        (&day11::fcn, false),
-        (&day12::fcn, false),
-        (&day13::fcn, false),
-        (&day14::fcn, false),
-        (&day15::fcn, false),
-        (&day16::fcn, false),
-        (&day17::fcn, false),
-        (&day18::fcn, false),
-        (&day19::fcn, false),
-        (&day20::fcn, false), // Not yet implemented
-        (&day21::fcn, true),
-    ];
+fn new(args: NewArgs) -> ExitCode {
+    let day = args.day as usize;
+    let new_day = match scaffold::new_day(Path::new("."), day, args.force) {
+        Ok(new_day) => new_day,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    for path in &new_day.created {
+        println!("Created {}", path.display());
+    }
+    for path in &new_day.updated {
+        println!("Updated {}", path.display());
+    }
+    println!("\nPut the input in data/day{}.txt and run the day with `cargo run -- run {}`", day, day);
+    return ExitCode::SUCCESS;
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let days = match args.days.resolve(DAYS.len()) {
+        Ok(days) => days,
+        Err(msg) => {
+            eprintln!("error: {}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.input.is_some() && days.len() != 1 {
+        eprintln!("error: --input can only be used when running a single day");
+        return ExitCode::FAILURE;
+    }
+    if let Some(name) = &args.implementation {
+        for &day in &days {
+            if let Err(e) = runner::implementation(day, name) {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let params = match args.params.load() {
+        Ok(params) => params,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let export = match &args.export {
+        Some(path) => match args.format.or_else(|| export::Format::from_path(path)) {
+            Some(format) => Some((path, format)),
+            None => {
+                eprintln!("error: cannot tell the export format of {}, use --format", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+
+    // Run each selected day on each of its inputs, carrying on with the rest when one fails
+    let options = RunOptions {
+        parts: cli::parts(args.part),
+        implementation: args.implementation.clone(),
+        verify: args.verify,
+        record: args.record,
+        sequential: args.sequential,
+        time_limit: cli::time_limit(args.time_limit),
+        params,
+        cache: Some(Arc::new(AnswerCache::load(Path::new(CACHE_FILE)))),
+        force: args.force,
+        render: args.render.clone(),
+    };
+    let tasks = runner::tasks(&days, Path::new(DATA_DIR), args.input.as_deref());
+    let (reports, wall_time) = time(|| runner::run_all(tasks, &options));
 
-    // Iterate over the days and run the function if the boolean is true
-    for (idx, (f, show)) in enumerate(&days) {
-        let day = idx + 1;
-        if *show {
-            println!("\nDay {day} result:\n");
-            let contents_j = setup(day);
-            (f)(&contents_j);
+    let timings = reports.iter().filter_map(|r| r.timings.clone()).collect::<Vec<_>>();
+    let failed_days = reports.iter().filter(|r| r.timings.is_none()).map(|r| r.name.as_str()).collect::<Vec<_>>();
+    let num_failed = reports.iter().map(|r| r.num_failed()).sum::<usize>();
+    if !timings.is_empty() {
+        timing::print_summary(&timings);
+        println!("\nWall time: {}", timing::format_duration(wall_time));
+    }
+    if memory::COUNTING {
+        let memory = reports.iter().filter_map(|r| Some((r.name.clone(), r.memory.clone()?))).collect::<Vec<_>>();
+        if !memory.is_empty() {
+            memory::print_summary(&memory);
         }
     }
+    if let Some(Err(e)) = options.cache.as_ref().map(|cache| cache.save()) {
+        eprintln!("\nwarning: could not save the answer cache: {}", e);
+    }
+    if let Some((path, format)) = export {
+        let records = reports.iter().map(|r| r.record.clone()).collect::<Vec<_>>();
+        match export::write(path, format, &records) {
+            Ok(()) => println!("\nResults written to {}", path.display()),
+            Err(e) => {
+                eprintln!("\nerror: could not export the results: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if !failed_days.is_empty() {
+        eprintln!("\nDays that could not be run: {}", failed_days.join(", "));
+    }
+    if num_failed > 0 {
+        eprintln!("\n{} part(s) failed, timed out or did not match the stored answers", num_failed);
+    }
+    if !failed_days.is_empty() || num_failed > 0 {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
 }
@@ -0,0 +1,158 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Whether the binary counts allocations, which it does when built with the `count-allocs` feature
+pub const COUNTING: bool = cfg!(feature = "count-allocs");
+
+thread_local! {
+    // Bytes currently allocated by this thread, which goes negative when it frees memory from another thread
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+    static TOTAL: Cell<usize> = const { Cell::new(0) };
+    static COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+// The counters can be gone while a thread is shutting down, in which case nothing is counted
+fn record(allocated: usize, freed: usize, count: usize) {
+    let _ = CURRENT.try_with(|current| {
+        current.set(current.get() + allocated as isize - freed as isize);
+        PEAK.with(|peak| peak.set(peak.get().max(current.get())));
+        TOTAL.with(|total| total.set(total.get() + allocated));
+        COUNT.with(|c| c.set(c.get() + count));
+    });
+}
+
+/// Global allocator that counts what each thread allocates on top of the system allocator.
+/// Install it with `#[global_allocator]` and read the counts with `measure`.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0, 1);
+        }
+        return ptr;
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0, 1);
+        }
+        return ptr;
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size(), 0);
+    }
+
+    // Growing or shrinking counts as allocating the new size and freeing the old one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size(), 1);
+        }
+        return new_ptr;
+    }
+}
+
+/// What some code allocated: the most it had allocated at once on top of what was already there,
+/// and the bytes and number of allocations it made in total
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub peak_bytes: usize,
+    pub total_bytes: usize,
+    pub allocations: usize,
+}
+
+/// Run `f`, counting what it allocates on this thread. Memory allocated on other threads, e.g. by rayon,
+/// is not counted, and without `CountingAllocator` installed everything is zero.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let start = CURRENT.get();
+    let (outer_peak, outer_total, outer_count) = (PEAK.replace(start), TOTAL.replace(0), COUNT.replace(0));
+    let ret = f();
+    let stats = AllocStats {
+        peak_bytes: (PEAK.get() - start).max(0) as usize,
+        total_bytes: TOTAL.get(),
+        allocations: COUNT.get(),
+    };
+    // Add what was measured to any measurement this one is part of
+    PEAK.set(PEAK.get().max(outer_peak));
+    TOTAL.set(outer_total + stats.total_bytes);
+    COUNT.set(outer_count + stats.allocations);
+    return (ret, stats);
+}
+
+/// Memory used while parsing the input and solving each part of a day, with parts that were not run left as `None`
+#[derive(Clone, Debug)]
+pub struct DayMemory {
+    pub parse: AllocStats,
+    pub part1: Option<AllocStats>,
+    pub part2: Option<AllocStats>,
+}
+
+/// Format a number of bytes in a binary unit that suits its size
+pub fn format_bytes(bytes: usize) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    return if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", size, units[unit]) };
+}
+
+/// Print a table with one row per phase of each named day, giving its peak and total allocated bytes
+/// and how many allocations it made
+pub fn print_summary(days: &[(String, DayMemory)]) {
+    let width = days.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max(5);
+    let row = |name: &str, cols: [String; 4]| {
+        println!("{:>width$} | {:<6} | {:>10} | {:>10} | {:>11}", name, cols[0], cols[1], cols[2], cols[3]);
+    };
+    println!();
+    row("Day", ["Phase", "Peak", "Total", "Allocations"].map(String::from));
+    println!("{}", "-".repeat(width + 50));
+    for (name, memory) in days {
+        let phases = [("Parse", Some(memory.parse)), ("Part 1", memory.part1), ("Part 2", memory.part2)];
+        for (phase, stats) in phases.into_iter().filter_map(|(phase, stats)| Some((phase, stats?))) {
+            let name = if phase == "Parse" { name.as_str() } else { "" };
+            row(name, [
+                phase.to_string(),
+                format_bytes(stats.peak_bytes),
+                format_bytes(stats.total_bytes),
+                stats.allocations.to_string(),
+            ]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn nested_measurements() {
+        // The test binary does not count allocations, so record them by hand
+        let ((_, inner), outer) = measure(|| {
+            record(100, 0, 1);
+            let inner = measure(|| {
+                record(1000, 0, 1);
+                record(0, 1000, 0);
+            });
+            record(0, 100, 0);
+            return inner;
+        });
+        assert_eq!(inner, AllocStats { peak_bytes: 1000, total_bytes: 1000, allocations: 1 });
+        assert_eq!(outer, AllocStats { peak_bytes: 1100, total_bytes: 1100, allocations: 2 });
+    }
+}
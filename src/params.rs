@@ -0,0 +1,129 @@
+use crate::error::{Error, Result};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File the puzzle parameters are read from unless another one is given
+pub const PARAMS_FILE: &str = "params.toml";
+
+/// Puzzle parameters of one day, e.g. the size of the grid, which are different for the examples.
+/// Days read them into a struct of their own, whose defaults are the values for the real puzzle.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DayParams(toml::Table);
+
+impl DayParams {
+    /// Parameters written as TOML, e.g. `size = [11, 7]`
+    pub fn from_toml(s: &str) -> Result<DayParams> {
+        return toml::from_str(s).map(DayParams).map_err(|e| Error::params(e.message()));
+    }
+
+    /// Read the parameters into `T`, which should fill in missing ones with `#[serde(default)]`
+    pub fn get<T: DeserializeOwned>(&self) -> Result<T> {
+        return T::deserialize(toml::Value::Table(self.0.clone())).map_err(|e| Error::params(e.message()));
+    }
+
+    /// The parameters written back as TOML
+    pub fn to_toml(&self) -> String {
+        return self.0.to_string();
+    }
+}
+
+/// Parameters of every day, from the `[dayN]` tables of a TOML file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params(toml::Table);
+
+// Check that a top level key names a day
+fn check_day_key(key: &str) -> Result<()> {
+    if key.strip_prefix("day").and_then(|day| day.parse::<usize>().ok()).is_some_and(|day| (1..=25).contains(&day)) {
+        return Ok(());
+    }
+    return Err(Error::params(format!("Expected a day such as `day14`, got `{}`", key)));
+}
+
+impl Params {
+    pub fn parse(s: &str) -> Result<Params> {
+        let table: toml::Table = toml::from_str(s).map_err(|e| Error::params(e.message()))?;
+        for (key, value) in &table {
+            check_day_key(key)?;
+            if !value.is_table() {
+                return Err(Error::params(format!("Expected `{}` to be a table of parameters", key)));
+            }
+        }
+        return Ok(Params(table));
+    }
+
+    /// Read the parameters from `path`. If it does not exist they are all left at their defaults,
+    /// unless `required` is set.
+    pub fn load(path: &Path, required: bool) -> Result<Params> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => return Ok(Params::default()),
+            Err(e) => return Err(Error::io(path, e)),
+        };
+        return Params::parse(&contents).map_err(|e| Error::params(format!("{}: {}", path.display(), e)));
+    }
+
+    /// Set one parameter from `dayN.name=value`, where the value is TOML (e.g. `[11, 7]`) or else a string
+    pub fn set(&mut self, assignment: &str) -> Result<()> {
+        let parsed = assignment.split_once('=').and_then(|(key, value)| Some((key.trim().split_once('.')?, value.trim())));
+        let Some(((day, name), value)) = parsed else {
+            return Err(Error::params(format!("Expected `dayN.name=value`, got `{}`", assignment)));
+        };
+        check_day_key(day)?;
+        let value = match toml::from_str::<toml::Table>(&format!("value = {}", value)) {
+            Ok(mut table) => table.remove("value").unwrap(),
+            Err(_) => toml::Value::String(value.to_string()),
+        };
+        let day_table = self.0.entry(day).or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        day_table.as_table_mut().unwrap().insert(name.to_string(), value);
+        return Ok(());
+    }
+
+    pub fn day(&self, day: usize) -> DayParams {
+        return match self.0.get(&format!("day{}", day)) {
+            Some(toml::Value::Table(table)) => DayParams(table.clone()),
+            _ => DayParams::default(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Grid {
+        size: (usize, usize),
+        steps: usize,
+    }
+
+    impl Default for Grid {
+        fn default() -> Self {
+            return Grid { size: (101, 103), steps: 100 };
+        }
+    }
+
+    #[test]
+    fn file_and_overrides() {
+        let mut params = Params::parse("[day14]\nsize = [11, 7]\n").unwrap();
+        assert_eq!(params.day(14).get::<Grid>().unwrap(), Grid { size: (11, 7), steps: 100 });
+        params.set("day14.steps = 5").unwrap();
+        assert_eq!(params.day(14).get::<Grid>().unwrap(), Grid { size: (11, 7), steps: 5 });
+        assert_eq!(params.day(18).get::<Grid>().unwrap(), Grid::default());
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert!(Params::parse("[dya14]\nsize = [11, 7]\n").is_err());
+        assert!(Params::default().set("day14.size").is_err());
+        assert!(Params::default().set("size=[11, 7]").is_err());
+        // Values that are not TOML are strings, which then have the wrong type
+        let mut params = Params::default();
+        params.set("day14.size=11,7").unwrap();
+        assert!(params.day(14).get::<Grid>().is_err());
+        assert!(DayParams::from_toml("sise = [11, 7]").unwrap().get::<Grid>().is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, Rgb, RgbImage};
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Colour of a pixel, as red, green and blue
+pub type Color = [u8; 3];
+
+pub const BLACK: Color = [0, 0, 0];
+pub const WHITE: Color = [255, 255, 255];
+pub const GREY: Color = [110, 110, 110];
+pub const RED: Color = [220, 40, 40];
+pub const GREEN: Color = [40, 190, 70];
+pub const BLUE: Color = [50, 100, 230];
+pub const YELLOW: Color = [240, 200, 40];
+
+// Cells are drawn as squares, as large as they can be for the image to stay within this many pixels
+const MAX_PIXELS: usize = 600;
+const MAX_CELL_PIXELS: usize = 20;
+
+/// Animations keep at most this many frames, spread over the steps they were given
+pub const MAX_FRAMES: usize = 300;
+
+// Time each frame of an animation is shown for
+const FRAME_MS: u32 = 80;
+
+// Where the part running on this thread renders to, and what its files are called, e.g. `out/day6`
+thread_local! {
+    static TARGET: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with the renderings of the days inside it written to `dir`, or with rendering off if there is none.
+/// Files are named after `name`, e.g. `day6` gives `dir/day6-walk.gif`.
+pub fn with_target<T>(dir: Option<&Path>, name: &str, f: impl FnOnce() -> T) -> T {
+    let previous = TARGET.replace(dir.map(|dir| dir.join(name)));
+    let ret = f();
+    TARGET.set(previous);
+    return ret;
+}
+
+/// Whether the part running on this thread should render, which days check before drawing anything costly
+pub fn enabled() -> bool {
+    return TARGET.with_borrow(|target| target.is_some());
+}
+
+// File to write a rendering to, creating its directory
+fn target_path(name: &str, extension: &str) -> Result<Option<PathBuf>> {
+    let Some(base) = TARGET.with_borrow(|target| target.clone()) else {
+        return Ok(None);
+    };
+    let path = PathBuf::from(format!("{}-{}.{}", base.display(), name, extension));
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+    }
+    return Ok(Some(path));
+}
+
+fn image_error(path: &Path, e: image::ImageError) -> Error {
+    return Error::io(path, io::Error::other(e));
+}
+
+/// Map of `rows` by `cols` cells where the cells at `points` are set, e.g. the robots of day 14
+pub fn points(rows: usize, cols: usize, points: impl IntoIterator<Item = (usize, usize)>) -> Grid<bool> {
+    let mut grid = Grid::new(rows, cols, false);
+    for pos in points {
+        if let Some(cell) = grid.get_mut(pos) {
+            *cell = true;
+        }
+    }
+    return grid;
+}
+
+/// Colours of the characters days draw their maps with: walls, boxes, paths and whoever moves around them
+pub fn map_colors(c: &char) -> Color {
+    return match c {
+        '.' => BLACK,
+        '#' => GREY,
+        'O' | '[' | ']' => YELLOW,
+        'X' => GREEN,
+        'S' | 'E' => BLUE,
+        '@' | '^' | 'v' | '<' | '>' => RED,
+        _ => WHITE,
+    };
+}
+
+/// Colours of set and unset cells
+pub fn bool_colors(cell: &bool) -> Color {
+    return if *cell { WHITE } else { BLACK };
+}
+
+/// Image of a grid with each cell drawn in the colour `palette` gives it
+pub fn image<T>(grid: &Grid<T>, palette: impl Fn(&T) -> Color) -> RgbImage {
+    let scale = (MAX_PIXELS / grid.rows().max(grid.cols()).max(1)).clamp(1, MAX_CELL_PIXELS);
+    let (width, height) = ((grid.cols() * scale) as u32, (grid.rows() * scale) as u32);
+    let colors = grid.map(palette);
+    return RgbImage::from_fn(width, height, |x, y| Rgb(colors[(y as usize / scale, x as usize / scale)]));
+}
+
+/// Write a grid to `<name>.png` if the running part renders
+pub fn png<T>(name: &str, grid: &Grid<T>, palette: impl Fn(&T) -> Color) -> Result<()> {
+    let Some(path) = target_path(name, "png")? else {
+        return Ok(());
+    };
+    log::debug!("Rendering {}", path.display());
+    return image(grid, palette).save(&path).map_err(|e| image_error(&path, e));
+}
+
+/// Frames of a grid changing step by step, written to `<name>.gif` by `finish`. Does nothing if the running
+/// part does not render. Only every so many steps are kept, so that there are at most `MAX_FRAMES`.
+pub struct Animation {
+    name: String,
+    frames: Vec<RgbImage>,
+    // Keep every `stride`th step, counting from 0
+    stride: usize,
+    step: usize,
+    enabled: bool,
+}
+
+impl Animation {
+    /// Animation of `steps` calls to `frame`, or of the first `MAX_FRAMES` if `steps` is 0 because it is not known
+    pub fn new(name: &str, steps: usize) -> Animation {
+        return Animation { name: name.to_string(), frames: Vec::new(), stride: steps.div_ceil(MAX_FRAMES).max(1), step: 0, enabled: enabled() };
+    }
+
+    /// Animation that keeps no frames, for runs that only need the answer
+    pub fn none() -> Animation {
+        return Animation { name: String::new(), frames: Vec::new(), stride: 1, step: 0, enabled: false };
+    }
+
+    /// Next step, which `draw` is only called for if its frame is kept
+    pub fn frame<T>(&mut self, draw: impl FnOnce() -> Grid<T>, palette: impl Fn(&T) -> Color) {
+        if self.enabled && self.step.is_multiple_of(self.stride) && self.frames.len() < MAX_FRAMES {
+            self.frames.push(image(&draw(), palette));
+        }
+        self.step += 1;
+    }
+
+    /// Write the frames, ending with `last` so that the final state is always shown
+    pub fn finish<T>(mut self, last: &Grid<T>, palette: impl Fn(&T) -> Color) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let Some(path) = target_path(&self.name, "gif")? else {
+            return Ok(());
+        };
+        self.frames.push(image(last, palette));
+        log::debug!("Rendering {} frames to {}", self.frames.len(), path.display());
+        let file = File::create(&path).map_err(|e| Error::io(&path, e))?;
+        // Speed 10 quantizes the colours much faster than the default, and the few colours used survive it
+        let mut encoder = GifEncoder::new_with_speed(file, 10);
+        encoder.set_repeat(Repeat::Infinite).map_err(|e| image_error(&path, e))?;
+        let delay = Delay::from_numer_denom_ms(FRAME_MS, 1);
+        let frames = self.frames.into_iter().map(|frame| Frame::from_parts(image::DynamicImage::from(frame).into_rgba8(), 0, 0, delay));
+        return encoder.encode_frames(frames).map_err(|e| image_error(&path, e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn cells_become_squares() {
+        let grid = Grid::parse_chars("#.\n.@").unwrap();
+        let img = image(&grid, map_colors);
+        assert_eq!(img.dimensions(), (40, 40));
+        assert_eq!(img.get_pixel(0, 0).0, GREY);
+        assert_eq!(img.get_pixel(39, 0).0, BLACK);
+        assert_eq!(img.get_pixel(25, 39).0, RED);
+        // Large maps get smaller cells
+        assert_eq!(image(&Grid::new(103, 101, false), bool_colors).dimensions(), (505, 515));
+        // Points off the map are left out
+        let robots = points(2, 3, [(1, 2), (5, 5)]);
+        assert_eq!(robots.iter().filter(|(_, &set)| set).map(|(pos, _)| pos).collect::<Vec<_>>(), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn only_renders_inside_a_target() {
+        let dir = env::temp_dir().join(format!("aoc-render-{}", std::process::id()));
+        let grid = Grid::new(3, 4, true);
+        png("map", &grid, bool_colors).unwrap();
+        assert!(!enabled());
+
+        with_target(Some(&dir), "day6", || {
+            png("map", &grid, bool_colors).unwrap();
+            let mut animation = Animation::new("walk", 2 * MAX_FRAMES);
+            let mut drawn = 0;
+            for _ in 0..2 * MAX_FRAMES {
+                animation.frame(|| { drawn += 1; grid.clone() }, bool_colors);
+            }
+            assert_eq!(drawn, MAX_FRAMES);
+            assert_eq!(animation.frames.len(), MAX_FRAMES);
+            animation.finish(&grid, bool_colors).unwrap();
+        });
+        assert_eq!(image::open(dir.join("day6-map.png")).unwrap().to_rgb8().dimensions(), (80, 60));
+        let gif = image::ImageReader::open(dir.join("day6-walk.gif")).unwrap().decode().unwrap();
+        assert_eq!((gif.width(), gif.height()), (80, 60));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
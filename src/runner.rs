@@ -0,0 +1,307 @@
+use crate::answers::{self, ExpectedAnswers};
+use crate::cache::{self, AnswerCache, CachedDay, CachedPart};
+use crate::error::{Error, Result};
+use crate::export::{self, DayRecord, PartRecord, PartStatus};
+use crate::input::{self, Input};
+use crate::memory::DayMemory;
+use crate::params::Params;
+use crate::render;
+use crate::solution::{Answer, DayResult, PartResult, Parts, Runner};
+use crate::timing::{self, time, DayTimings};
+use crate::*; // The day modules
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// List of days, where `DAYS[i]` solves day `i+1`
+pub const DAYS: [&dyn Runner; 21] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20, // Not yet implemented
+    &day21::Day21,
+];
+
+/// A named implementation of some parts of a day
+#[derive(Clone, Copy)]
+pub struct Implementation {
+    pub name: &'static str,
+    pub runner: &'static dyn Runner,
+    pub parts: Parts,
+}
+
+/// Name of the implementation of each day in `DAYS`
+pub const MAIN: &str = "main";
+
+/// Other implementations of some days, which can be run instead with `--impl` or compared with the main one
+pub const ALTERNATIVES: [(usize, Implementation); 3] = [
+    (12, Implementation { name: "old", runner: &day12::Day12Old, parts: Parts::Part1 }),
+    (17, Implementation { name: "search", runner: &day17::Day17Search, parts: Parts::Part2 }),
+    (21, Implementation { name: "v2", runner: &day21_2::Day21, parts: Parts::Part1 }),
+];
+
+/// Every implementation of a day, starting with the main one
+pub fn implementations(day: usize) -> Vec<Implementation> {
+    let main = Implementation { name: MAIN, runner: DAYS[day - 1], parts: Parts::Both };
+    let alternatives = ALTERNATIVES.iter().filter(|(d, _)| *d == day).map(|(_, implementation)| *implementation);
+    return std::iter::once(main).chain(alternatives).collect();
+}
+
+/// Implementation of a day with the given name
+pub fn implementation(day: usize, name: &str) -> Result<Implementation> {
+    let implementations = implementations(day);
+    if let Some(implementation) = implementations.iter().find(|i| i.name == name) {
+        return Ok(*implementation);
+    }
+    let names = implementations.iter().map(|i| i.name).collect::<Vec<_>>();
+    return Err(Error::command(format!("Day {} has no implementation `{}` (it has {})", day, name, names.join(", "))));
+}
+
+/// How to run the days
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    pub parts: Parts,
+    // Name of the implementation to run instead of the main one
+    pub implementation: Option<String>,
+    // Check the answers against the stored ones
+    pub verify: bool,
+    // Store answers for parts that have no stored answer yet, which implies `verify`
+    pub record: bool,
+    // Run the days one after another instead of in parallel
+    pub sequential: bool,
+    // How long each part gets before it is reported as timed out, see `budget`
+    pub time_limit: Option<Duration>,
+    pub params: Params,
+    // Answers of earlier runs, which parts are not run again for unless `force` is set
+    pub cache: Option<Arc<AnswerCache>>,
+    pub force: bool,
+    // Directory the days render their maps to, see `render`
+    pub render: Option<PathBuf>,
+}
+
+/// Every (day, input) pair to run for the given days, reading `data_dir` unless an input is given.
+/// Days whose inputs cannot be listed keep the error, so that they are reported as failed.
+pub fn tasks(days: &[usize], data_dir: &Path, explicit: Option<&Path>) -> Vec<(usize, Result<Input>)> {
+    let mut tasks = Vec::new();
+    for &day in days {
+        let inputs = match explicit {
+            Some(arg) => Ok(vec![input::explicit_input(arg)]),
+            None => input::day_inputs(data_dir, day),
+        };
+        match inputs {
+            Ok(inputs) => tasks.extend(inputs.into_iter().map(|input| (day, Ok(input)))),
+            Err(e) => tasks.push((day, Err(e))),
+        }
+    }
+    return tasks;
+}
+
+/// Run the selected parts of a day on one input and write the answers to `out`.
+/// Returns the timings, the memory used and what happened to each part.
+pub fn run_day(day: usize, input: &Input, options: &RunOptions, out: &mut String) -> Result<(DayTimings, DayMemory, Vec<PartRecord>)> {
+    let implementation = implementation(day, options.implementation.as_deref().unwrap_or(MAIN))?;
+    let Some(parts) = options.parts.and(implementation.parts) else {
+        let part = if options.parts == Parts::Part1 { 1 } else { 2 };
+        return Err(Error::command(format!("Implementation `{}` of day {} does not solve part {}", implementation.name, day, part)));
+    };
+    if implementation.name != MAIN {
+        writeln!(out, "Using implementation `{}`", implementation.name).unwrap();
+    }
+    let (contents, load_time) = time(|| input.read());
+    let contents = contents?;
+    let params = options.params.day(day);
+
+    // Only run the parts that have no cached answers from this version of the implementation
+    let cache_key = cache::key(day, implementation.name, &contents, &params);
+    let stored = match &options.cache {
+        Some(cache) => cache.get(&cache_key, cache::version(implementation.runner.type_name())),
+        None => CachedDay::default(),
+    };
+    // Cached parts would not draw anything, so rendering runs every part again
+    let hits = if options.force || options.render.is_some() { CachedDay::default() } else { stored.clone() };
+    let cached = |part: u8| hits.part(part).is_some();
+    let to_run = Parts::select(parts.part1() && !cached(1), parts.part2() && !cached(2));
+    let mut result = match to_run {
+        Some(to_run) => {
+            // Inputs given with `--input` are labelled with their path, of which only the file name is kept
+            let name = match input.label.as_deref().and_then(|label| Path::new(label).file_stem()) {
+                Some(label) => format!("day{}-{}", day, label.to_string_lossy()),
+                None => format!("day{}", day),
+            };
+            render::with_target(options.render.as_deref(), &name, || implementation.runner.run(&contents, &params, to_run, options.time_limit))?
+        }
+        None => DayResult { parse_time: Duration::from_nanos(hits.parse_ns), ..Default::default() },
+    };
+    if let (Some(cache), Some(_)) = (&options.cache, to_run) {
+        cache.insert(cache_key, stored.updated(&result));
+    }
+    if parts.part1() && cached(1) {
+        result.part1 = hits.part1.as_ref().map(CachedPart::result);
+    }
+    if parts.part2() && cached(2) {
+        result.part2 = hits.part2.as_ref().map(CachedPart::result);
+    }
+    let timings = DayTimings {
+        day,
+        label: input.label.clone(),
+        load: load_time,
+        parse: result.parse_time,
+        part1: result.part1.as_ref().map(|p| p.time),
+        part2: result.part2.as_ref().map(|p| p.time),
+    };
+    // Parts that were not run used no memory this time
+    let memory = DayMemory {
+        parse: result.parse_memory,
+        part1: result.part1.as_ref().filter(|_| !cached(1)).map(|p| p.memory),
+        part2: result.part2.as_ref().filter(|_| !cached(2)).map(|p| p.memory),
+    };
+
+    // Compare against the stored answers, recording new ones if asked to.
+    // Input from stdin has nowhere to store answers, so its answers are always new.
+    let verify = options.verify || options.record;
+    let path = input.path().filter(|_| verify);
+    let mut expected = match path {
+        Some(path) => ExpectedAnswers::load(path)?,
+        None => ExpectedAnswers::default(),
+    };
+    let mut recorded = false;
+    let mut parts = Vec::new();
+    for (part, PartResult { answer, time, .. }) in result.parts() {
+        let mut record = PartRecord {
+            part,
+            answer: None,
+            expected: expected.part(part).clone(),
+            status: PartStatus::Unchecked,
+            time_ns: export::nanos(*time),
+            cached: cached(part),
+            error: None,
+        };
+        let answer = match answer {
+            Ok(answer) => answer,
+            Err(e @ Error::Timeout(_)) => {
+                writeln!(out, "Part {}: {}", part, e).unwrap();
+                parts.push(PartRecord { status: PartStatus::Timeout, error: Some(e.to_string()), ..record });
+                continue;
+            }
+            Err(e) => {
+                writeln!(out, "Part {}: failed: {}", part, e).unwrap();
+                parts.push(PartRecord { status: PartStatus::Error, error: Some(e.to_string()), ..record });
+                continue;
+            }
+        };
+        record.answer = Some(answer.to_string());
+        let from_cache = if cached(part) && *answer != Answer::Unsolved { " (cached)" } else { "" };
+        if !verify || *answer == Answer::Unsolved {
+            writeln!(out, "Part {}: {}{}", part, answer, from_cache).unwrap();
+            if *answer == Answer::Unsolved {
+                record.answer = None;
+                record.status = PartStatus::Unsolved;
+            }
+            parts.push(record);
+            continue;
+        }
+        let status = answers::check(expected.part(part), answer);
+        writeln!(out, "Part {}: {} [{}]{}", part, answer, status, from_cache).unwrap();
+        record.status = match status {
+            answers::Status::Pass => PartStatus::Pass,
+            answers::Status::Fail(_) => PartStatus::Fail,
+            answers::Status::New => PartStatus::New,
+        };
+        parts.push(record);
+        if options.record && path.is_some() {
+            recorded |= expected.record(part, answer);
+        }
+    }
+    if let (true, Some(path)) = (recorded, path) {
+        expected.save(path)?;
+        writeln!(out, "Recorded new answers in {}", answers::answers_path(path).display()).unwrap();
+    }
+    return Ok((timings, memory, parts));
+}
+
+/// Everything a day printed while running on one input, buffered so that days run in parallel
+/// can still be printed in order. `timings` and `memory` are `None` if the day could not be run.
+pub struct DayReport {
+    pub name: String,
+    pub output: String,
+    pub timings: Option<DayTimings>,
+    pub memory: Option<DayMemory>,
+    pub record: DayRecord,
+}
+
+impl DayReport {
+    // How many parts failed, timed out or did not match the stored answers
+    pub fn num_failed(&self) -> usize {
+        return self.record.parts.iter().filter(|p| matches!(p.status, PartStatus::Fail | PartStatus::Error | PartStatus::Timeout)).count();
+    }
+}
+
+pub fn report_day(day: usize, input: Result<Input>, options: &RunOptions) -> DayReport {
+    let label = input.as_ref().ok().and_then(|input| input.label.clone());
+    let name = timing::day_name(day, &label);
+    let mut output = format!("\nDay {} result:\n\n", name);
+    let result = input.and_then(|input| run_day(day, &input, options, &mut output));
+    let (timings, memory, record) = match result {
+        Ok((timings, memory, parts)) => {
+            let record = DayRecord::new(day, label, Some(&timings), parts, None);
+            (Some(timings), Some(memory), record)
+        }
+        Err(e) => {
+            writeln!(output, "Failed: {}", e).unwrap();
+            (None, None, DayRecord::new(day, label, None, vec![], Some(e.to_string())))
+        }
+    };
+    return DayReport { name, output, timings, memory, record };
+}
+
+/// Run every (day, input) pair, in parallel unless asked not to, printing each report in order
+/// as soon as it and all the ones before it are done
+pub fn run_all(tasks: Vec<(usize, Result<Input>)>, options: &RunOptions) -> Vec<DayReport> {
+    let mut reports = Vec::with_capacity(tasks.len());
+    if options.sequential {
+        for (day, input) in tasks {
+            let report = report_day(day, input, options);
+            print!("{}", report.output);
+            reports.push(report);
+        }
+        return reports;
+    }
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        scope.spawn(move || {
+            tasks.into_par_iter().enumerate().for_each_with(sender, |sender, (idx, (day, input))| {
+                sender.send((idx, report_day(day, input, options))).unwrap();
+            });
+        });
+        let mut finished = BTreeMap::new();
+        for (idx, report) in receiver {
+            finished.insert(idx, report);
+            while let Some(report) = finished.remove(&reports.len()) {
+                print!("{}", report.output);
+                reports.push(report);
+            }
+        }
+    });
+    return reports;
+}
+
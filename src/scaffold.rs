@@ -0,0 +1,137 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Module for a new day, with `__DAY__` standing for the day number
+const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// Source of the module for a new day
+pub fn day_source(day: usize) -> String {
+    return TEMPLATE.replace("__DAY__", &day.to_string());
+}
+
+// Add `pub mod dayN;` to `lib.rs` after the module of the latest day before it.
+// Returns `None` if the module is already declared.
+fn register_module(lib: &str, day: usize) -> Option<String> {
+    let re = Regex::new(r"(?m)^pub mod day(\d+);.*\n").unwrap();
+    let mut insert_at = None;
+    for m in re.find_iter(lib) {
+        let other: usize = re.captures(m.as_str()).unwrap()[1].parse().unwrap();
+        if other == day {
+            return None;
+        }
+        if other < day || insert_at.is_none() {
+            insert_at = Some(if other < day { m.end() } else { m.start() });
+        }
+    }
+    let insert_at = insert_at.unwrap_or(lib.len());
+    return Some(format!("{}pub mod day{};\n{}", &lib[..insert_at], day, &lib[insert_at..]));
+}
+
+// Add `&dayN::DayN` to the end of `DAYS` in `runner.rs`, which has to list every day before it.
+// Returns `None` if the day is already listed.
+fn register_runner(runner: &str, day: usize) -> Result<Option<String>> {
+    let re = Regex::new(r"DAYS: \[&dyn Runner; (\d+)\] = \[").unwrap();
+    let Some(cap) = re.captures(runner) else {
+        return Err(Error::command("Could not find the list of days in src/runner.rs"));
+    };
+    let num_days: usize = cap[1].parse().unwrap();
+    if day <= num_days {
+        return Ok(None);
+    }
+    if day == num_days + 2 {
+        return Err(Error::command(format!("Day {} has to be added before day {}", num_days + 1, day)));
+    }
+    if day > num_days + 2 {
+        return Err(Error::command(format!("Days {}-{} have to be added before day {}", num_days + 1, day - 1, day)));
+    }
+    let list = cap.get(0).unwrap();
+    let Some(list_end) = runner[list.end()..].find("\n];").map(|i| list.end() + i + 1) else {
+        return Err(Error::command("Could not find the end of the list of days in src/runner.rs"));
+    };
+    return Ok(Some(format!(
+        "{}DAYS: [&dyn Runner; {}] = [{}    &day{}::Day{},\n{}",
+        &runner[..list.start()],
+        day,
+        &runner[list.end()..list_end],
+        day,
+        day,
+        &runner[list_end..]
+    )));
+}
+
+/// Files created and changed when adding a day
+#[derive(Debug, Default)]
+pub struct NewDay {
+    pub created: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+}
+
+/// Create `src/dayN.rs` from the template, register it in `src/lib.rs` and `runner::DAYS`, and create an empty
+/// `data/dayN.txt` to put the input in. `root` is the root of the project. An existing module is only replaced
+/// with `force`, while an existing input is always kept.
+pub fn new_day(root: &Path, day: usize, force: bool) -> Result<NewDay> {
+    let read = |path: &Path| fs::read_to_string(path).map_err(|e| Error::io(path, e));
+    let write = |path: &Path, contents: &str| fs::write(path, contents).map_err(|e| Error::io(path, e));
+    let module_path = root.join(format!("src/day{}.rs", day));
+    let lib_path = root.join("src/lib.rs");
+    let runner_path = root.join("src/runner.rs");
+    let input_path = root.join(format!("data/day{}.txt", day));
+    if module_path.exists() && !force {
+        return Err(Error::command(format!("{} already exists, use --force to replace it", module_path.display())));
+    }
+
+    // Work out all the changes before writing anything, so that nothing is left half done
+    let lib = register_module(&read(&lib_path)?, day);
+    let runner = register_runner(&read(&runner_path)?, day)?;
+    let mut new_day = NewDay::default();
+    write(&module_path, &day_source(day))?;
+    new_day.created.push(module_path);
+    if let Some(lib) = lib {
+        write(&lib_path, &lib)?;
+        new_day.updated.push(lib_path);
+    }
+    if let Some(runner) = runner {
+        write(&runner_path, &runner)?;
+        new_day.updated.push(runner_path);
+    }
+    if !input_path.exists() {
+        fs::create_dir_all(input_path.parent().unwrap()).map_err(|e| Error::io(&input_path, e))?;
+        write(&input_path, "")?;
+        new_day.created.push(input_path);
+    }
+    return Ok(new_day);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modules_are_registered_in_order() {
+        let lib = "pub mod answers;\npub mod day1;\npub mod day3; // TODO\npub mod day3_2;\n";
+        assert_eq!(register_module(lib, 2).unwrap(), "pub mod answers;\npub mod day1;\npub mod day2;\npub mod day3; // TODO\npub mod day3_2;\n");
+        assert_eq!(register_module(lib, 4).unwrap(), "pub mod answers;\npub mod day1;\npub mod day3; // TODO\npub mod day4;\npub mod day3_2;\n");
+        assert_eq!(register_module(lib, 3), None);
+    }
+
+    #[test]
+    fn days_are_appended_to_the_runner() {
+        let runner = "const DAYS: [&dyn Runner; 2] = [\n    &day1::Day1,\n    &day2::Day2, // Stub\n];\n";
+        assert_eq!(
+            register_runner(runner, 3).unwrap().unwrap(),
+            "const DAYS: [&dyn Runner; 3] = [\n    &day1::Day1,\n    &day2::Day2, // Stub\n    &day3::Day3,\n];\n"
+        );
+        assert!(register_runner(runner, 2).unwrap().is_none());
+        assert_eq!(register_runner(runner, 4).unwrap_err().to_string(), "Day 3 has to be added before day 4");
+        assert_eq!(register_runner(runner, 5).unwrap_err().to_string(), "Days 3-4 have to be added before day 5");
+    }
+
+    #[test]
+    fn template_is_filled_in() {
+        let source = day_source(22);
+        assert!(source.contains("impl Solution for Day22 {"));
+        assert!(!source.contains("__DAY__"));
+    }
+}
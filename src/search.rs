@@ -0,0 +1,288 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// What a search found: the cheapest cost of every state it reached, and every state each one is reached from
+/// on a cheapest path. Together these are a DAG of all the cheapest paths from the start.
+/// The predecessors of a state are complete once the search is done with it, which is always true for the
+/// goal of `bfs` and `dijkstra`. `astar` may stop before it has seen every cheapest way into the goal.
+#[derive(Clone, Debug)]
+pub struct Search<S, C> {
+    pub costs: HashMap<S, C>,
+    pub predecessors: HashMap<S, Vec<S>>,
+    /// First goal state the search reached, where it stopped
+    pub goal: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Search<S, C> {
+    fn new(start: S, zero: C) -> Search<S, C> {
+        return Search { costs: HashMap::from([(start, zero)]), predecessors: HashMap::new(), goal: None };
+    }
+
+    pub fn cost(&self, state: &S) -> Option<C> {
+        return self.costs.get(state).copied();
+    }
+
+    /// A cheapest path from the start to `state`, including both, if `state` was reached
+    pub fn path(&self, state: &S) -> Option<Vec<S>> {
+        if !self.costs.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        while let Some(prev) = self.predecessors.get(path.last().unwrap()) {
+            path.push(prev[0].clone());
+        }
+        path.reverse();
+        return Some(path);
+    }
+
+    // States with no predecessors are where the paths start
+    fn predecessors_of(&self, state: &S) -> &[S] {
+        return self.predecessors.get(state).map_or(&[], |prev| prev.as_slice());
+    }
+
+    /// Every cheapest path from the start to `state`. There can be very many, see `count_paths`.
+    pub fn all_paths(&self, state: &S) -> Vec<Vec<S>> {
+        if !self.costs.contains_key(state) {
+            return Vec::new();
+        }
+        // Extend partial paths backwards from `state` until they reach the start
+        let mut paths = Vec::new();
+        let mut partial = vec![vec![state.clone()]];
+        while let Some(path) = partial.pop() {
+            let prev = self.predecessors_of(path.last().unwrap());
+            if prev.is_empty() {
+                paths.push(path.into_iter().rev().collect());
+                continue;
+            }
+            for p in prev {
+                let mut longer = path.clone();
+                longer.push(p.clone());
+                partial.push(longer);
+            }
+        }
+        return paths;
+    }
+
+    /// Every state on a cheapest path from the start to any of `ends`, including the start and the ends
+    pub fn on_cheapest_paths<'a>(&self, ends: impl IntoIterator<Item = &'a S>) -> HashSet<S> where S: 'a {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<S> = ends.into_iter().filter(|end| self.costs.contains_key(end)).cloned().collect();
+        while let Some(state) = stack.pop() {
+            if seen.contains(&state) {
+                continue;
+            }
+            stack.extend(self.predecessors_of(&state).iter().cloned());
+            seen.insert(state);
+        }
+        return seen;
+    }
+
+    /// Number of cheapest paths from the start to `state`, without listing them
+    pub fn count_paths(&self, state: &S) -> usize where C: Ord {
+        // Predecessors are cheaper than the states they lead to, so going by cost counts them first
+        let mut states: Vec<S> = self.on_cheapest_paths([state]).into_iter().collect();
+        states.sort_by_key(|s| self.costs[s]);
+        let mut counts: HashMap<S, usize> = HashMap::new();
+        for s in states {
+            let prev = self.predecessors_of(&s);
+            let count = if prev.is_empty() { 1 } else { prev.iter().map(|p| counts[p]).sum() };
+            counts.insert(s, count);
+        }
+        return counts.get(state).copied().unwrap_or(0);
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        return self.cost(self.goal.as_ref()?);
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        return self.path(self.goal.as_ref()?);
+    }
+}
+
+/// Breadth-first search from `start`, where every step costs 1. Stops at the first state that `goal` accepts,
+/// or goes through every reachable state if it accepts none, e.g. `|_| false`.
+pub fn bfs<S, I>(start: S, mut neighbors: impl FnMut(&S) -> I, mut goal: impl FnMut(&S) -> bool) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        if goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        let cost = search.costs[&state] + 1;
+        for next in neighbors(&state) {
+            match search.costs.get(&next) {
+                None => {
+                    search.costs.insert(next.clone(), cost);
+                    search.predecessors.insert(next.clone(), vec![state.clone()]);
+                    queue.push_back(next);
+                }
+                // Another way in that is just as short
+                Some(&known) if known == cost => search.predecessors.entry(next).or_default().push(state.clone()),
+                Some(_) => {}
+            }
+        }
+    }
+    return search;
+}
+
+/// Dijkstra's algorithm from `start`, where `neighbors` gives the states one step away with the cost of the
+/// step. Stops at the first state that `goal` accepts, which is a cheapest one, like `bfs`.
+pub fn dijkstra<S, C, I>(start: S, neighbors: impl FnMut(&S) -> I, goal: impl FnMut(&S) -> bool) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    return astar(start, neighbors, |_| C::default(), goal);
+}
+
+// State waiting in the heap, which pops the lowest estimate first
+struct Queued<S, C> {
+    estimate: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Queued<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.estimate == other.estimate;
+    }
+}
+
+impl<S, C: Ord> Eq for Queued<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Queued<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl<S, C: Ord> Ord for Queued<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return other.estimate.cmp(&self.estimate);
+    }
+}
+
+/// A* search from `start`, which is Dijkstra's algorithm guided towards the goal by `heuristic`, an estimate of
+/// the cost left from a state. The estimate must never be more than the real cost, or the path found may not
+/// be a cheapest one, and the zero heuristic of `dijkstra` is always safe. Steps that cost nothing would let
+/// the predecessors go round in circles, so every step should cost more than zero.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new(start.clone(), C::default());
+    let mut heap = BinaryHeap::from([Queued { estimate: heuristic(&start), cost: C::default(), state: start }]);
+    while let Some(Queued { cost, state, .. }) = heap.pop() {
+        // States are queued again when a cheaper path to them is found, and the old entries are skipped
+        if cost > search.costs[&state] {
+            continue;
+        }
+        if goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            match search.costs.get(&next) {
+                Some(&known) if known < next_cost => continue,
+                // Another way in that is just as cheap
+                Some(&known) if known == next_cost => {
+                    search.predecessors.entry(next).or_default().push(state.clone());
+                    continue;
+                }
+                _ => {}
+            }
+            search.costs.insert(next.clone(), next_cost);
+            search.predecessors.insert(next.clone(), vec![state.clone()]);
+            heap.push(Queued { estimate: next_cost + heuristic(&next), cost: next_cost, state: next });
+        }
+    }
+    return search;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "S.#.
+.##.
+...E";
+
+    fn open_neighbors(grid: &Grid<char>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        return grid.neighbors4(pos).filter(|&next| grid[next] != '#').collect();
+    }
+
+    #[test]
+    fn bfs_finds_a_shortest_path() {
+        let grid = Grid::parse_chars(MAZE).unwrap();
+        let found = bfs((0, 0), |&pos| open_neighbors(&grid, pos), |&pos| grid[pos] == 'E');
+        assert_eq!(found.goal, Some((2, 3)));
+        assert_eq!(found.goal_cost(), Some(5));
+        assert_eq!(found.goal_path().unwrap(), vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (2, 3)]);
+        // Without a goal it reaches every open cell
+        let everything = bfs((0, 0), |&pos| open_neighbors(&grid, pos), |_| false);
+        assert_eq!(everything.cost(&(0, 3)), Some(7));
+        assert_eq!(everything.goal, None);
+        assert_eq!(everything.path(&(0, 2)), None);
+    }
+
+    #[test]
+    fn dijkstra_takes_the_cheaper_detour() {
+        // 0 -> 1 -> 3 costs 2 + 2, while the direct step costs 5
+        let edges: Vec<Vec<(usize, u32)>> = vec![vec![(1, 2), (3, 5)], vec![(2, 9), (3, 2)], vec![], vec![(2, 1)]];
+        let found = dijkstra(0, |&u| edges[u].clone(), |&u| u == 2);
+        assert_eq!(found.goal_cost(), Some(5));
+        assert_eq!(found.goal_path().unwrap(), vec![0, 1, 3, 2]);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = Grid::parse_chars(MAZE).unwrap();
+        let end = Point::new(2, 3);
+        let steps = |&pos: &(usize, usize)| open_neighbors(&grid, pos).into_iter().map(|next| (next, 1));
+        let guided = astar((0, 0), steps, |&pos| Point::from(pos).manhattan(end), |&pos| grid[pos] == 'E');
+        let plain = dijkstra((0, 0), steps, |&pos| grid[pos] == 'E');
+        assert_eq!(guided.goal_cost(), Some(5));
+        assert_eq!(guided.goal_cost(), plain.goal_cost());
+    }
+
+    #[test]
+    fn every_cheapest_path() {
+        // Across an open 3x3 grid there are 6 shortest paths between opposite corners, which cover every cell
+        let grid = Grid::new(3, 3, '.');
+        let found = bfs((0, 0), |&pos| open_neighbors(&grid, pos), |&pos| pos == (2, 2));
+        assert_eq!(found.count_paths(&(2, 2)), 6);
+        let paths = found.all_paths(&(2, 2));
+        assert_eq!(paths.len(), 6);
+        assert!(paths.iter().all(|path| path.len() == 5 && path[0] == (0, 0) && path[4] == (2, 2)));
+        assert_eq!(found.on_cheapest_paths([&(2, 2)]).len(), 9);
+        assert_eq!(found.on_cheapest_paths([&(0, 2)]).len(), 3);
+
+        // Ties in cost are kept as well, but not the dearer way in
+        let edges: Vec<Vec<(usize, u32)>> = vec![vec![(1, 1), (2, 2), (3, 5)], vec![(3, 3)], vec![(3, 2)], vec![]];
+        let found = dijkstra(0, |&u| edges[u].clone(), |_| false);
+        assert_eq!(found.predecessors[&3], vec![1, 2]);
+        assert_eq!(found.count_paths(&3), 2);
+        assert_eq!(found.on_cheapest_paths([&3]), HashSet::from([0, 1, 2, 3]));
+        assert_eq!(found.count_paths(&0), 1);
+        assert!(found.all_paths(&4).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::budget;
+use crate::error::{catch_panic, Result};
+use crate::memory::{self, AllocStats};
+use crate::params::DayParams;
+use crate::timing::time;
+use std::fmt;
+use std::time::Duration;
+
+/// Answer to one part of a puzzle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Answer::Int(x) => write!(f, "{}", x),
+            Answer::UInt(x) => write!(f, "{}", x),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "Not yet implemented"),
+        };
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(x: i32) -> Self {
+        return Answer::Int(x as i64);
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(x: i64) -> Self {
+        return Answer::Int(x);
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(x: u64) -> Self {
+        return Answer::UInt(x);
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(x: usize) -> Self {
+        return Answer::UInt(x as u64);
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        return Answer::Text(s);
+    }
+}
+
+/// Which parts of a day should be run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parts {
+    Both,
+    Part1,
+    Part2,
+}
+
+impl Parts {
+    pub fn part1(self) -> bool {
+        return self != Parts::Part2;
+    }
+
+    pub fn part2(self) -> bool {
+        return self != Parts::Part1;
+    }
+
+    /// Parts selected by both, if there are any
+    pub fn and(self, other: Parts) -> Option<Parts> {
+        return Parts::select(self.part1() && other.part1(), self.part2() && other.part2());
+    }
+
+    /// The parts for which the flag is set, if there are any
+    pub fn select(part1: bool, part2: bool) -> Option<Parts> {
+        return match (part1, part2) {
+            (true, true) => Some(Parts::Both),
+            (true, false) => Some(Parts::Part1),
+            (false, true) => Some(Parts::Part2),
+            (false, false) => None,
+        };
+    }
+}
+
+/// A day's puzzle: parse the input once, then solve each part from the parsed input
+pub trait Solution {
+    type Input;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input>;
+
+    // Days with puzzle parameters read them into their input, and parse with the defaults
+    fn parse_with(&self, contents: &str, _params: &DayParams) -> Result<Self::Input> {
+        return self.parse(contents);
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+
+    // Days that are only partially solved leave part 2 out
+    fn part2(&self, _input: &Self::Input) -> Result<Answer> {
+        return Ok(Answer::Unsolved);
+    }
+}
+
+/// Answer to one part, or why it could not be solved, along with how long it took and what it allocated
+#[derive(Debug)]
+pub struct PartResult {
+    pub answer: Result<Answer>,
+    pub time: Duration,
+    pub memory: AllocStats,
+}
+
+/// Results of the parts that were run for a day
+#[derive(Debug, Default)]
+pub struct DayResult {
+    pub parse_time: Duration,
+    pub parse_memory: AllocStats,
+    pub part1: Option<PartResult>,
+    pub part2: Option<PartResult>,
+}
+
+impl DayResult {
+    /// Iterate over `(part, result)` for the parts that were run
+    pub fn parts(&self) -> impl Iterator<Item = (u8, &PartResult)> {
+        return [(1, &self.part1), (2, &self.part2)]
+            .into_iter()
+            .filter_map(|(part, result)| result.as_ref().map(|result| (part, result)));
+    }
+}
+
+/// Parsed input of a day, whose parts can be solved any number of times, e.g. in benchmarks
+pub trait Parsed {
+    fn part1(&self) -> Result<Answer>;
+    fn part2(&self) -> Result<Answer>;
+}
+
+struct ParsedInput<'a, S: Solution> {
+    solution: &'a S,
+    input: S::Input,
+}
+
+impl<S: Solution> Parsed for ParsedInput<'_, S> {
+    fn part1(&self) -> Result<Answer> {
+        return self.solution.part1(&self.input);
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        return self.solution.part2(&self.input);
+    }
+}
+
+/// Object safe view of a `Solution`, so the runner can keep every day in one list
+pub trait Runner: Sync {
+    // Fails if the input cannot be parsed, while failures of each part are kept in the result.
+    // Each part gets `time_limit` to run in, if given.
+    fn run(&self, contents: &str, params: &DayParams, parts: Parts, time_limit: Option<Duration>) -> Result<DayResult>;
+
+    // Parse the input and keep it, to solve the parts separately
+    fn parse_input<'a>(&'a self, contents: &str, params: &DayParams) -> Result<Box<dyn Parsed + 'a>>;
+
+    // Full name of the solution type, e.g. `advent_of_code::day12::Day12Old`, which tells which module it is in
+    fn type_name(&self) -> &'static str;
+}
+
+impl<S: Solution + Sync> Runner for S where S::Input: 'static {
+    fn run(&self, contents: &str, params: &DayParams, parts: Parts, time_limit: Option<Duration>) -> Result<DayResult> {
+        let ((input, parse_time), parse_memory) = memory::measure(|| time(|| catch_panic(|| self.parse_with(contents, params))));
+        let input = input?;
+        // A part that panics fails on its own, leaving the other part and the other days to run
+        let run_part = |part: fn(&Self, &S::Input) -> Result<Answer>| {
+            let ((answer, time), memory) = memory::measure(|| time(|| budget::with_limit(time_limit, || catch_panic(|| part(self, &input)))));
+            return PartResult { answer, time, memory };
+        };
+        let part1 = if parts.part1() { Some(run_part(Self::part1)) } else { None };
+        let part2 = if parts.part2() { Some(run_part(Self::part2)) } else { None };
+        return Ok(DayResult { parse_time, parse_memory, part1, part2 });
+    }
+
+    fn parse_input<'a>(&'a self, contents: &str, params: &DayParams) -> Result<Box<dyn Parsed + 'a>> {
+        return Ok(Box::new(ParsedInput { solution: self, input: self.parse_with(contents, params)? }));
+    }
+
+    fn type_name(&self) -> &'static str {
+        return std::any::type_name::<S>();
+    }
+}
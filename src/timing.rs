@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+/// Run `f`, returning its result and how long it took
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let ret = f();
+    return (ret, start.elapsed());
+}
+
+/// Format a duration with a fixed number of digits in a unit that suits its size
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos() as f64;
+    return if nanos < 1e3 {
+        format!("{:.0} ns", nanos)
+    } else if nanos < 1e6 {
+        format!("{:.1} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.1} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    };
+}
+
+/// How long each phase of a day took, with parts that were not run left as `None`
+#[derive(Clone, Debug)]
+pub struct DayTimings {
+    pub day: usize,
+    // Which input the day ran on, if not its usual one
+    pub label: Option<String>,
+    pub load: Duration,
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+impl DayTimings {
+    pub fn total(&self) -> Duration {
+        return self.load + self.parse + self.part1.unwrap_or_default() + self.part2.unwrap_or_default();
+    }
+}
+
+/// Name of a day run on a labelled input, e.g. `6 (alice)`
+pub fn day_name(day: usize, label: &Option<String>) -> String {
+    return match label {
+        Some(label) => format!("{} ({})", day, label),
+        None => day.to_string(),
+    };
+}
+
+/// Print a table with one row per day and one column per phase, followed by the totals
+pub fn print_summary(timings: &[DayTimings]) {
+    let optional = |d: Option<Duration>| d.map(format_duration).unwrap_or("-".to_string());
+    let names = timings.iter().map(|t| day_name(t.day, &t.label)).collect::<Vec<_>>();
+    let width = names.iter().map(|name| name.chars().count()).max().unwrap_or(0).max(5);
+    let row = |name: &str, cols: [String; 5]| {
+        println!("{:>width$} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10}", name, cols[0], cols[1], cols[2], cols[3], cols[4]);
+    };
+    println!();
+    row("Day", ["Load", "Parse", "Part 1", "Part 2", "Total"].map(String::from));
+    println!("{}", "-".repeat(width + 5 * 13));
+    for (t, name) in timings.iter().zip(&names) {
+        row(name, [
+            format_duration(t.load),
+            format_duration(t.parse),
+            optional(t.part1),
+            optional(t.part2),
+            format_duration(t.total()),
+        ]);
+    }
+    if timings.len() > 1 {
+        println!("{}", "-".repeat(width + 5 * 13));
+        let sum = |f: fn(&DayTimings) -> Option<Duration>| timings.iter().filter_map(f).sum::<Duration>();
+        row("All", [
+            format_duration(sum(|t| Some(t.load))),
+            format_duration(sum(|t| Some(t.parse))),
+            format_duration(sum(|t| t.part1)),
+            format_duration(sum(|t| t.part2)),
+            format_duration(sum(|t| Some(t.total()))),
+        ]);
+    }
+}
@@ -0,0 +1,101 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use std::io::Write;
+
+// Log targets of the days start with this, e.g. `advent_of_code::day9`
+const CRATE: &str = env!("CARGO_CRATE_NAME");
+
+/// Day whose module a log target comes from, e.g. 21 for `advent_of_code::day21_2`
+pub fn target_day(target: &str) -> Option<usize> {
+    let rest = target.strip_prefix(CRATE)?.strip_prefix("::day")?;
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    return rest[..end].parse().ok();
+}
+
+/// Writes the diagnostics that the days log with `log::debug!` and `log::trace!` to stderr, so that they
+/// never mix with the answers. Debug messages summarise what a part found, trace messages dump the state
+/// as it goes.
+pub struct DayLogger {
+    level: LevelFilter,
+    // Only log for these days, or for the whole crate if `None`
+    days: Option<Vec<usize>>,
+}
+
+impl DayLogger {
+    pub fn new(level: LevelFilter, days: Option<Vec<usize>>) -> DayLogger {
+        return DayLogger { level, days };
+    }
+}
+
+impl Log for DayLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if metadata.level() > self.level || !metadata.target().starts_with(CRATE) {
+            return false;
+        }
+        return match &self.days {
+            Some(days) => target_day(metadata.target()).is_some_and(|day| days.contains(&day)),
+            None => true,
+        };
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // Prefix every line of a dump, so that days logging in parallel can be told apart
+        let module = record.target().rsplit("::").next().unwrap();
+        let mut stderr = std::io::stderr().lock();
+        for line in record.args().to_string().lines() {
+            let _ = writeln!(stderr, "[{}] {}", module, line);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+/// Install the logger for messages up to `level`, only from `days` if given. Nothing is logged by default.
+pub fn init(level: LevelFilter, days: Option<Vec<usize>>) {
+    if level == LevelFilter::Off || log::set_boxed_logger(Box::new(DayLogger::new(level, days))).is_err() {
+        return;
+    }
+    log::set_max_level(level);
+}
+
+/// Level for a `-v` count: debug for one, trace for more
+pub fn verbosity_level(verbose: u8) -> LevelFilter {
+    return match verbose {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    #[test]
+    fn days_from_targets() {
+        assert_eq!(target_day("advent_of_code::day9"), Some(9));
+        assert_eq!(target_day("advent_of_code::day21_2"), Some(21));
+        assert_eq!(target_day("advent_of_code::runner"), None);
+        assert_eq!(target_day("image::codecs::png"), None);
+    }
+
+    #[test]
+    fn filters_by_level_and_day() {
+        let metadata = |level, target| Metadata::builder().level(level).target(target).build();
+        let logger = DayLogger::new(LevelFilter::Debug, Some(vec![2, 9]));
+        assert!(logger.enabled(&metadata(Level::Debug, "advent_of_code::day9")));
+        assert!(!logger.enabled(&metadata(Level::Trace, "advent_of_code::day9")));
+        // Day 2 does not select day 21
+        assert!(!logger.enabled(&metadata(Level::Debug, "advent_of_code::day21")));
+        assert!(!logger.enabled(&metadata(Level::Debug, "advent_of_code::runner")));
+
+        let logger = DayLogger::new(LevelFilter::Trace, None);
+        assert!(logger.enabled(&metadata(Level::Trace, "advent_of_code::runner")));
+        assert!(!logger.enabled(&metadata(Level::Debug, "rayon_core")));
+    }
+}
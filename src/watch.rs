@@ -0,0 +1,217 @@
+use crate::error::{Error, Result};
+use crate::export::{self, DayRecord, PartRecord, PartStatus};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked for changes
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Files that are watched for a day: its modules, e.g. `src/day21.rs` and `src/day21_2.rs`, which also hold
+/// the examples its tests run on, and its inputs, `data/dayN.txt` and `data/dayN/`
+pub fn day_paths(src_dir: &Path, data_dir: &Path, day: usize) -> Result<Vec<PathBuf>> {
+    let module = format!("day{}", day);
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(src_dir).map_err(|e| Error::io(src_dir, e))? {
+        let path = entry.map_err(|e| Error::io(src_dir, e))?.path();
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        if stem == module || stem.strip_prefix(&module).is_some_and(|rest| rest.starts_with('_')) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths.push(data_dir.join(format!("day{}.txt", day)));
+    paths.push(data_dir.join(format!("day{}", day)));
+    return Ok(paths);
+}
+
+// When each watched file was last modified and how big it is, where directories stand for the files in them
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    let mut files = Snapshot::new();
+    for path in paths {
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(|entry| Some(entry.ok()?.path())).collect(),
+            Err(_) => vec![path.clone()],
+        };
+        for file in entries {
+            if let Ok(meta) = fs::metadata(&file) {
+                files.insert(file, (meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), meta.len()));
+            }
+        }
+    }
+    return files;
+}
+
+/// Notices when watched files are changed, created or removed, by polling them
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    last: Snapshot,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Watcher {
+        let last = snapshot(&paths);
+        return Watcher { paths, last };
+    }
+
+    /// Whether any of the files changed since the last call
+    pub fn changed(&mut self) -> bool {
+        let now = snapshot(&self.paths);
+        if now == self.last {
+            return false;
+        }
+        self.last = now;
+        return true;
+    }
+
+    /// Wait until the files change. Editors often write a file more than once when saving,
+    /// so this waits for one more interval and takes in those writes as well.
+    pub fn wait(&mut self, interval: Duration) {
+        while !self.changed() {
+            thread::sleep(interval);
+        }
+        thread::sleep(interval);
+        self.changed();
+    }
+}
+
+// Cargo that is running this, so that the same toolchain builds the day
+fn cargo() -> Command {
+    return Command::new(env::var_os("CARGO").unwrap_or("cargo".into()));
+}
+
+fn run_cargo(command: &mut Command) -> Result<bool> {
+    let status = command.status().map_err(|e| Error::command(format!("Could not run cargo: {}", e)))?;
+    return Ok(status.success());
+}
+
+/// Run the unit tests of a day's modules, returning whether they passed
+pub fn run_tests(day: usize) -> Result<bool> {
+    // Test names start with the module, and alternative modules are named e.g. `day21_2`
+    let filters = [format!("day{}::", day), format!("day{}_", day)];
+    return run_cargo(cargo().args(["test", "--quiet", "--lib", "--"]).args(filters));
+}
+
+/// File the results of a watched day are exported to
+pub fn export_path(target_dir: &Path, day: usize) -> PathBuf {
+    return target_dir.join(format!("watch-day{}.json", day));
+}
+
+/// Build and run a day with `cargo run -- run`, passing on `args`, and read back what it exported to `export`
+pub fn run_day(day: usize, args: &[String], export: &Path) -> Result<Vec<DayRecord>> {
+    let _ = fs::remove_file(export);
+    let mut command = cargo();
+    command.args(["run", "--quiet", "--", "run", &day.to_string(), "--format", "json", "--export"]).arg(export).args(args);
+    // The run fails when a part does, but it still exports what happened
+    run_cargo(&mut command)?;
+    if !export.exists() {
+        return Err(Error::command(format!("Day {} could not be built or run", day)));
+    }
+    return export::read_json(export);
+}
+
+// Answer to a part, or what went wrong
+fn outcome(part: &PartRecord) -> String {
+    return match (&part.answer, part.status) {
+        (Some(answer), _) => answer.clone(),
+        (None, PartStatus::Unsolved) => "unsolved".to_string(),
+        (None, _) => part.error.clone().unwrap_or_else(|| "failed".to_string()),
+    };
+}
+
+// Outcome of every part on every input, keyed by the input's label and the part, with part 0 for errors
+// that kept the day from running
+fn outcomes(records: &[DayRecord]) -> BTreeMap<(Option<String>, u8), String> {
+    let mut outcomes = BTreeMap::new();
+    for day in records {
+        if let Some(error) = &day.error {
+            outcomes.insert((day.input.clone(), 0), format!("failed: {}", error));
+        }
+        for part in &day.parts {
+            outcomes.insert((day.input.clone(), part.part), outcome(part));
+        }
+    }
+    return outcomes;
+}
+
+/// A line per part comparing its answers in two runs, e.g. `Part 1: 41 -> 42`
+pub fn changes(previous: &[DayRecord], current: &[DayRecord]) -> Vec<String> {
+    let before = outcomes(previous);
+    let mut lines = Vec::new();
+    for (key, now) in outcomes(current) {
+        let (input, part) = &key;
+        let mut name = if *part == 0 { "Day".to_string() } else { format!("Part {}", part) };
+        if let Some(input) = input {
+            name += &format!(" ({})", input);
+        }
+        lines.push(match before.get(&key) {
+            Some(was) if *was == now => format!("{}: {} (unchanged)", name, now),
+            Some(was) => format!("{}: {} -> {}", name, was, now),
+            None => format!("{}: {} (new)", name, now),
+        });
+    }
+    return lines;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(input: Option<&str>, parts: &[(u8, Option<&str>, PartStatus)]) -> DayRecord {
+        let parts = parts.iter().map(|&(part, answer, status)| PartRecord {
+            part,
+            answer: answer.map(String::from),
+            expected: None,
+            status,
+            time_ns: 1,
+            cached: false,
+            error: if status == PartStatus::Timeout { Some("TIMEOUT after 1.00 s".to_string()) } else { None },
+        });
+        return DayRecord::new(3, input.map(String::from), None, parts.collect(), None);
+    }
+
+    #[test]
+    fn answers_are_compared_by_input_and_part() {
+        let previous = [record(None, &[(1, Some("41"), PartStatus::Unchecked), (2, None, PartStatus::Unsolved)])];
+        let current = [
+            record(None, &[(1, Some("42"), PartStatus::Unchecked), (2, None, PartStatus::Unsolved)]),
+            record(Some("alice"), &[(1, None, PartStatus::Timeout)]),
+        ];
+        assert_eq!(changes(&previous, &current), vec![
+            "Part 1: 41 -> 42",
+            "Part 2: unsolved (unchanged)",
+            "Part 1 (alice): TIMEOUT after 1.00 s (new)",
+        ]);
+    }
+
+    #[test]
+    fn notices_changed_and_new_files() {
+        let dir = env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("data")).unwrap();
+        for name in ["day3.rs", "day3_2.rs", "day31.rs", "lib.rs"] {
+            fs::write(dir.join("src").join(name), "").unwrap();
+        }
+        let paths = day_paths(&dir.join("src"), &dir.join("data"), 3).unwrap();
+        let names = paths.iter().map(|path| path.strip_prefix(&dir).unwrap().to_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["src/day3.rs", "src/day3_2.rs", "data/day3.txt", "data/day3"]);
+
+        let mut watcher = Watcher::new(paths);
+        assert!(!watcher.changed());
+        fs::write(dir.join("src/day3.rs"), "// changed").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        fs::create_dir_all(dir.join("data/day3")).unwrap();
+        fs::write(dir.join("data/day3/alice.txt"), "1").unwrap();
+        assert!(watcher.changed());
+        fs::write(dir.join("src/lib.rs"), "// not watched").unwrap();
+        assert!(!watcher.changed());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+
+fn parse_contents(contents: &str) -> Result<Vec<String>> {
+    return Ok(contents.lines().map(String::from).collect());
+}
+
+fn part1(_lines: &Vec<String>) -> Answer {
+    return Answer::Unsolved;
+}
+
+fn part2(_lines: &Vec<String>) -> Answer {
+    return Answer::Unsolved;
+}
+
+pub struct Day__DAY__;
+
+impl Solution for Day__DAY__ {
+    type Input = Vec<String>;
+
+    fn parse(&self, contents: &str) -> Result<Self::Input> {
+        return parse_contents(contents);
+    }
+
+    fn part1(&self, lines: &Self::Input) -> Result<Answer> {
+        return Ok(part1(lines));
+    }
+
+    fn part2(&self, lines: &Self::Input) -> Result<Answer> {
+        return Ok(part2(lines));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "the example has not been filled in yet"]
+    fn example_part1() {
+        let input = Day__DAY__.parse(EXAMPLE).unwrap();
+        assert_eq!(Day__DAY__.part1(&input).unwrap().to_string(), "");
+    }
+
+    #[test]
+    #[ignore = "the example has not been filled in yet"]
+    fn example_part2() {
+        let input = Day__DAY__.parse(EXAMPLE).unwrap();
+        assert_eq!(Day__DAY__.part2(&input).unwrap().to_string(), "");
+    }
+}